@@ -0,0 +1,255 @@
+use std::io::{self, Write};
+use std::ops::RangeInclusive;
+
+use crate::scoring::optimal_attempts;
+use crate::solver::{solve, LyingOracle, Strategy};
+use crate::{GuessingGame, SecretSource, SeededSource};
+
+/// How to benchmark strategies. Every strategy run with the same settings faces the same secrets.
+#[derive(Debug, Clone)]
+pub struct Bench {
+    pub range: RangeInclusive<u32>,
+    pub games: usize,
+    pub seed: u64,
+    /// Chance of the feedback for each guess being a lie
+    pub lie_rate: f64,
+    /// A game that takes this many attempts is abandoned
+    pub give_up_after: u32,
+}
+
+impl Bench {
+    pub fn new(range: RangeInclusive<u32>, games: usize, seed: u64) -> Self {
+        // Generous enough for any sensible strategy, so only hopeless games are cut short
+        let give_up_after = optimal_attempts(&range) * 10;
+
+        Bench {
+            range,
+            games,
+            seed,
+            lie_rate: 0.0,
+            give_up_after,
+        }
+    }
+
+    pub fn run(&self, strategy: &mut dyn Strategy) -> Stats {
+        let mut secrets = SeededSource::new(self.seed);
+        let mut oracle = LyingOracle::new(self.lie_rate, self.seed.wrapping_add(1));
+
+        let mut attempts = Vec::with_capacity(self.games);
+        let mut gave_up = 0;
+
+        for _ in 0..self.games {
+            let mut game = GuessingGame::new(secrets.secret(self.range.clone()), self.range.clone());
+
+            let oracle = if self.lie_rate > 0.0 { Some(&mut oracle) } else { None };
+
+            if solve(&mut game, strategy, oracle, self.give_up_after) {
+                attempts.push(game.attempts());
+            } else {
+                gave_up += 1;
+            }
+        }
+
+        attempts.sort_unstable();
+
+        Stats {
+            strategy: strategy.name(),
+            attempts,
+            gave_up,
+            budget: optimal_attempts(&self.range),
+        }
+    }
+}
+
+/// How a strategy did over a benchmark
+#[derive(Debug, Clone)]
+pub struct Stats {
+    pub strategy: &'static str,
+    /// Attempts taken by every game that was won, smallest first
+    attempts: Vec<u32>,
+    pub gave_up: usize,
+    /// The attempts `--limited` mode allows for the range
+    pub budget: u32,
+}
+
+impl Stats {
+    pub fn games(&self) -> usize {
+        self.attempts.len() + self.gave_up
+    }
+
+    pub fn mean(&self) -> f64 {
+        if self.attempts.is_empty() {
+            return 0.0;
+        }
+
+        self.attempts.iter().map(|&a| f64::from(a)).sum::<f64>() / self.attempts.len() as f64
+    }
+
+    pub fn median(&self) -> u32 {
+        self.attempts.get(self.attempts.len() / 2).copied().unwrap_or(0)
+    }
+
+    pub fn max(&self) -> u32 {
+        self.attempts.last().copied().unwrap_or(0)
+    }
+
+    /// Fraction of all games won within the `--limited` budget
+    pub fn within_budget(&self) -> f64 {
+        if self.games() == 0 {
+            return 0.0;
+        }
+
+        let within = self.attempts.iter().filter(|&&a| a <= self.budget).count();
+
+        within as f64 / self.games() as f64
+    }
+
+    /// How many won games took each number of attempts, for every count that happened
+    pub fn histogram(&self) -> Vec<(u32, usize)> {
+        let mut histogram: Vec<(u32, usize)> = Vec::new();
+
+        for &attempts in &self.attempts {
+            match histogram.last_mut() {
+                Some((last, count)) if *last == attempts => *count += 1,
+                _                                        => histogram.push((attempts, 1)),
+            }
+        }
+
+        histogram
+    }
+
+    /// Print a summary and a histogram of attempts.
+    ///
+    /// Every attempt count within the budget gets its own row; games past the budget (marked with
+    /// `!`) are grouped into a handful of wider rows so a long tail doesn't scroll off the screen.
+    pub fn write_report<W: Write>(&self, mut output: W) -> io::Result<()> {
+        const BAR_WIDTH: usize = 40;
+        const TAIL_ROWS: u32 = 8;
+
+        writeln!(output, "{}: {} games, {} gave up", self.strategy, self.games(), self.gave_up)?;
+        writeln!(
+            output,
+            "  mean {:.2}  median {}  max {}  within budget of {}: {:.1}%",
+            self.mean(),
+            self.median(),
+            self.max(),
+            self.budget,
+            self.within_budget() * 100.0,
+        )?;
+
+        // (first, last, count) for each row
+        let mut rows: Vec<(u32, u32, usize)> = Vec::new();
+        let tail_width = (self.max().saturating_sub(self.budget)).div_ceil(TAIL_ROWS).max(1);
+
+        for (attempts, count) in self.histogram() {
+            let (first, last) = if attempts <= self.budget {
+                (attempts, attempts)
+            } else {
+                let first = self.budget + 1 + (attempts - self.budget - 1) / tail_width * tail_width;
+                (first, first + tail_width - 1)
+            };
+
+            match rows.last_mut() {
+                Some(row) if row.0 == first => row.2 += count,
+                _                           => rows.push((first, last, count)),
+            }
+        }
+
+        let tallest = rows.iter().map(|&(_, _, count)| count).max().unwrap_or(1);
+
+        for (first, last, count) in rows {
+            let label = if first == last { first.to_string() } else { format!("{first}-{last}") };
+            let marker = if first > self.budget { "!" } else { " " };
+            let bar = "#".repeat((count * BAR_WIDTH).div_ceil(tallest));
+
+            writeln!(output, "  {label:>7}{marker}| {bar:<BAR_WIDTH$} {count}")?;
+        }
+
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::solver::{strategy_by_name, BinarySearch};
+
+    fn stats(mut attempts: Vec<u32>, gave_up: usize, budget: u32) -> Stats {
+        attempts.sort_unstable();
+
+        Stats { strategy: "test", attempts, gave_up, budget }
+    }
+
+    #[test]
+    fn summary_numbers() {
+        let stats = stats(vec![3, 1, 7, 3, 9], 1, 7);
+
+        assert_eq!(stats.games(), 6);
+        assert_eq!(stats.mean(), 23.0 / 5.0);
+        assert_eq!(stats.median(), 3);
+        assert_eq!(stats.max(), 9);
+    }
+
+    #[test]
+    fn median_of_an_even_count_is_the_upper_one() {
+        assert_eq!(stats(vec![1, 2, 3, 4], 0, 7).median(), 3);
+        assert_eq!(stats(vec![5], 0, 7).median(), 5);
+    }
+
+    #[test]
+    fn within_budget_counts_games_given_up_on() {
+        // 3 of 5 within 7, one over and one given up on
+        assert_eq!(stats(vec![3, 7, 7, 8], 1, 7).within_budget(), 0.6);
+        assert_eq!(stats(vec![1, 2], 0, 7).within_budget(), 1.0);
+    }
+
+    #[test]
+    fn histogram_counts_each_number_of_attempts() {
+        assert_eq!(stats(vec![4, 2, 4, 7, 2, 2], 0, 7).histogram(), [(2, 3), (4, 2), (7, 1)]);
+    }
+
+    #[test]
+    fn no_games() {
+        let stats = stats(Vec::new(), 0, 7);
+
+        assert_eq!((stats.mean(), stats.median(), stats.max(), stats.within_budget()), (0.0, 0, 0, 0.0));
+        assert!(stats.histogram().is_empty());
+    }
+
+    #[test]
+    fn report_groups_the_tail_past_the_budget() {
+        let mut report = Vec::new();
+        stats(vec![1, 2, 2, 9, 30], 0, 2).write_report(&mut report).unwrap();
+
+        assert_eq!(String::from_utf8(report).unwrap(), "\
+test: 5 games, 0 gave up
+  mean 8.80  median 2  max 30  within budget of 2: 60.0%
+        1 | ####################                     1
+        2 | ######################################## 2
+     7-10!| ####################                     1
+    27-30!| ####################                     1
+");
+    }
+
+    #[test]
+    fn binary_search_always_fits_the_budget() {
+        let stats = Bench::new(1..=100, 1000, 7).run(&mut BinarySearch::new());
+
+        assert_eq!((stats.games(), stats.gave_up), (1000, 0));
+        assert!(stats.max() <= 7);
+        assert_eq!(stats.within_budget(), 1.0);
+    }
+
+    #[test]
+    fn the_same_seed_gives_the_same_stats() {
+        let run = |seed| {
+            let mut bench = Bench::new(1..=1000, 200, seed);
+            bench.lie_rate = 0.1;
+
+            bench.run(strategy_by_name("tolerant", seed, 1).unwrap().as_mut()).histogram()
+        };
+
+        assert_eq!(run(3), run(3));
+        assert_ne!(run(3), run(4));
+    }
+}
@@ -0,0 +1,261 @@
+use std::path::PathBuf;
+
+use clap::{Args, Parser, Subcommand, ValueEnum};
+use log::LevelFilter;
+
+use ch2_guessing_game::solver::STRATEGIES;
+use ch2_guessing_game::{Date, Difficulty};
+
+/// Guess the number!
+#[derive(Debug, Parser)]
+#[command(name = "ch2_guessing_game", version, args_conflicts_with_subcommands = true)]
+pub struct Cli {
+    #[command(flatten)]
+    pub verbosity: Verbosity,
+
+    #[command(subcommand)]
+    pub command: Option<Command>,
+
+    /// Options for playing, used when no subcommand is given
+    #[command(flatten)]
+    pub play: PlayArgs,
+}
+
+#[derive(Debug, Subcommand)]
+pub enum Command {
+    /// Print the high-score leaderboard
+    Scores(ScoresArgs),
+
+    /// Think of a number and let the computer guess it
+    Reverse(ReverseArgs),
+
+    /// Play lots of seeded games with solver strategies and compare how they do
+    Bench(BenchArgs),
+
+    /// Host one secret number for many players over TCP
+    Serve(ServeArgs),
+
+    /// Re-run a recorded transcript and check the game still says the same things
+    Replay(ReplayArgs),
+
+    /// Crack a 4-digit code from bulls and cows, Mastermind style
+    Mastermind(MastermindArgs),
+}
+
+/// How much gets logged to stderr. Stdout only ever carries the game itself.
+#[derive(Debug, Args)]
+pub struct Verbosity {
+    /// Log everything, including the secret number and every guess
+    #[arg(long, global = true, conflicts_with_all = ["verbose", "quiet"])]
+    pub debug: bool,
+
+    /// Log session details such as the seed and difficulty
+    #[arg(short, long, global = true, conflicts_with = "quiet")]
+    pub verbose: bool,
+
+    /// Only log errors
+    #[arg(short, long, global = true)]
+    pub quiet: bool,
+}
+
+impl Verbosity {
+    pub fn level(&self) -> LevelFilter {
+        if self.debug {
+            LevelFilter::Debug
+        } else if self.verbose {
+            LevelFilter::Info
+        } else if self.quiet {
+            LevelFilter::Error
+        } else {
+            LevelFilter::Warn
+        }
+    }
+}
+
+/// The range the secret number is picked from
+#[derive(Debug, Args)]
+pub struct RangeArgs {
+    /// Preset range: easy (1-10), normal (1-100) or hard (1-10000)
+    #[arg(long, default_value = "normal", conflicts_with_all = ["range", "min"])]
+    pub difficulty: Difficulty,
+
+    /// Custom range, as MIN-MAX
+    #[arg(long, value_parser = Difficulty::parse_range, conflicts_with = "min")]
+    pub range: Option<Difficulty>,
+
+    /// Lower end of a custom range
+    #[arg(long, requires = "max")]
+    pub min: Option<u32>,
+
+    /// Upper end of a custom range
+    #[arg(long, requires = "min")]
+    pub max: Option<u32>,
+}
+
+impl RangeArgs {
+    /// The difficulty picked by `--difficulty`, `--range` or `--min`/`--max`
+    pub fn difficulty(&self) -> Result<Difficulty, String> {
+        match (self.range, self.min, self.max) {
+            (Some(range), _, _)          => Ok(range),
+            (None, Some(min), Some(max)) => Difficulty::custom(min, max),
+            _                            => Ok(self.difficulty),
+        }
+    }
+}
+
+#[derive(Debug, Args)]
+pub struct PlayArgs {
+    /// Seed for the secret number, to replay a session exactly
+    #[arg(long)]
+    pub seed: Option<u64>,
+
+    #[command(flatten)]
+    pub range: RangeArgs,
+
+    /// Limit the round to the attempts a perfect binary search needs
+    #[arg(long, conflicts_with = "attempts")]
+    pub limited: bool,
+
+    /// Limit the round to this many attempts
+    #[arg(long, value_parser = clap::value_parser!(u32).range(1..))]
+    pub attempts: Option<u32>,
+
+    /// Say how hot or cold each wrong guess is
+    #[arg(long)]
+    pub hints: bool,
+
+    /// Name to record high scores under (defaults to $USER)
+    #[arg(long)]
+    pub name: Option<String>,
+
+    /// Where to keep high scores (defaults to the XDG data dir)
+    #[arg(long)]
+    pub scores_file: Option<PathBuf>,
+
+    /// Save a transcript of the session to this file (JSON Lines), for `replay`
+    #[arg(long)]
+    pub record: Option<PathBuf>,
+
+    /// Play full-screen, with a live range bar (only when stdin and stdout are terminals)
+    #[arg(long, conflicts_with = "record")]
+    pub tui: bool,
+
+    /// What the secret is
+    #[arg(long, value_enum, default_value_t = Kind::Number)]
+    pub kind: Kind,
+
+    /// Word list for `--kind word`, one word per line (defaults to a built-in list)
+    #[arg(long)]
+    pub dictionary: Option<PathBuf>,
+
+    /// How close counts as right for `--kind float`
+    #[arg(long, default_value_t = 0.01)]
+    pub tolerance: f64,
+
+    /// Earliest secret date for `--kind date`, as YYYY-MM-DD
+    #[arg(long, default_value = "1900-01-01")]
+    pub from: Date,
+
+    /// Latest secret date for `--kind date`, as YYYY-MM-DD
+    #[arg(long, default_value = "2099-12-31")]
+    pub to: Date,
+}
+
+/// Kinds of secret to play with. Only numbers have hints, transcripts, the TUI and high scores.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, ValueEnum)]
+pub enum Kind {
+    /// A whole number in the `--difficulty` range
+    Number,
+    /// A word from the dictionary; "too small" means it comes earlier
+    Word,
+    /// A date between `--from` and `--to`
+    Date,
+    /// A decimal number in the `--difficulty` range, right to within `--tolerance`
+    Float,
+}
+
+#[derive(Debug, Args)]
+pub struct ScoresArgs {
+    /// Only show rounds played at this difficulty
+    #[arg(long)]
+    pub difficulty: Option<Difficulty>,
+
+    /// How many rounds to show
+    #[arg(long, default_value_t = 10)]
+    pub limit: usize,
+
+    /// Where high scores are kept (defaults to the XDG data dir)
+    #[arg(long)]
+    pub scores_file: Option<PathBuf>,
+}
+
+#[derive(Debug, Args)]
+pub struct ReverseArgs {
+    #[command(flatten)]
+    pub range: RangeArgs,
+}
+
+#[derive(Debug, Args)]
+pub struct BenchArgs {
+    #[command(flatten)]
+    pub range: RangeArgs,
+
+    /// Games to play per strategy
+    #[arg(long, default_value_t = 10_000)]
+    pub games: usize,
+
+    /// Seed for the secrets (and any randomness in the strategies)
+    #[arg(long, default_value_t = 0)]
+    pub seed: u64,
+
+    /// Strategies to run (all of them by default)
+    #[arg(long, value_parser = STRATEGIES)]
+    pub strategy: Vec<String>,
+
+    /// Chance of each answer being a lie, from 0 to 1
+    #[arg(long, default_value_t = 0.0)]
+    pub lie_rate: f64,
+
+    /// Lies per game the tolerant strategy expects
+    #[arg(long, default_value_t = 1)]
+    pub max_lies: u32,
+}
+
+#[derive(Debug, Args)]
+pub struct ServeArgs {
+    #[command(flatten)]
+    pub range: RangeArgs,
+
+    /// Port to listen on
+    #[arg(long, default_value_t = 7878)]
+    pub port: u16,
+
+    /// Address to listen on
+    #[arg(long, default_value = "127.0.0.1")]
+    pub bind: String,
+
+    /// Seed for the secret numbers
+    #[arg(long)]
+    pub seed: Option<u64>,
+}
+
+#[derive(Debug, Args)]
+pub struct ReplayArgs {
+    /// Transcript recorded with `--record`
+    pub file: PathBuf,
+}
+
+#[derive(Debug, Args)]
+pub struct MastermindArgs {
+    /// Seed for the secret code
+    #[arg(long)]
+    pub seed: Option<u64>,
+
+    /// Limit the round to this many attempts
+    #[arg(long, value_parser = clap::value_parser!(u32).range(1..))]
+    pub attempts: Option<u32>,
+
+    /// Watch the minimax solver crack the code instead of playing
+    #[arg(long)]
+    pub solve: bool,
+}
@@ -0,0 +1,147 @@
+use std::fmt;
+use std::ops::RangeInclusive;
+use std::str::FromStr;
+
+use serde::{Deserialize, Serialize};
+
+/// How wide the range of possible secret numbers is
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default, Serialize, Deserialize)]
+#[serde(into = "String", try_from = "String")]
+pub enum Difficulty {
+    Easy,
+    #[default]
+    Normal,
+    Hard,
+    Custom { min: u32, max: u32 },
+}
+
+impl Difficulty {
+    /// A custom range. There has to be more than one number in it for there to be anything to guess.
+    pub fn custom(min: u32, max: u32) -> Result<Difficulty, String> {
+        if min >= max {
+            return Err(format!("invalid range: min ({min}) must be less than max ({max})"));
+        }
+
+        Ok(Difficulty::Custom { min, max })
+    }
+
+    /// A custom range written as `MIN-MAX` or `MIN..=MAX`
+    pub fn parse_range(s: &str) -> Result<Difficulty, String> {
+        let (min, max) = s.split_once("..=")
+            .or_else(|| s.split_once('-'))
+            .ok_or(format!("invalid range: {s} (expected MIN-MAX)"))?;
+
+        let min = min.trim().parse().map_err(|_| format!("invalid range: {s} (expected MIN-MAX)"))?;
+        let max = max.trim().parse().map_err(|_| format!("invalid range: {s} (expected MIN-MAX)"))?;
+
+        Difficulty::custom(min, max)
+    }
+
+    pub fn range(&self) -> RangeInclusive<u32> {
+        match *self {
+            Difficulty::Easy                => 1..=10,
+            Difficulty::Normal              => 1..=100,
+            Difficulty::Hard                => 1..=10_000,
+            Difficulty::Custom { min, max } => min..=max,
+        }
+    }
+}
+
+impl fmt::Display for Difficulty {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        match self {
+            Difficulty::Easy                => write!(f, "easy"),
+            Difficulty::Normal              => write!(f, "normal"),
+            Difficulty::Hard                => write!(f, "hard"),
+            Difficulty::Custom { min, max } => write!(f, "custom({min}-{max})"),
+        }
+    }
+}
+
+impl FromStr for Difficulty {
+    type Err = String;
+
+    // Accepts the presets by name, plus `custom(min-max)` as written by `Display`
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        let s = s.to_ascii_lowercase();
+
+        if let Some(bounds) = s.strip_prefix("custom(").and_then(|rest| rest.strip_suffix(')')) {
+            return Difficulty::parse_range(bounds);
+        }
+
+        match s.as_str() {
+            "easy"   => Ok(Difficulty::Easy),
+            "normal" => Ok(Difficulty::Normal),
+            "hard"   => Ok(Difficulty::Hard),
+            other    => Err(format!("unknown difficulty: {other} (expected easy, normal or hard)")),
+        }
+    }
+}
+
+// Serialized the same way it's displayed, e.g. "hard" or "custom(1-50)"
+impl From<Difficulty> for String {
+    fn from(difficulty: Difficulty) -> String {
+        difficulty.to_string()
+    }
+}
+
+impl TryFrom<String> for Difficulty {
+    type Error = String;
+
+    fn try_from(s: String) -> Result<Self, Self::Error> {
+        s.parse()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn ranges_are_min_dash_max_or_rust_style() {
+        assert_eq!(Difficulty::parse_range("1-50"), Ok(Difficulty::Custom { min: 1, max: 50 }));
+        assert_eq!(Difficulty::parse_range(" 5 ..= 9 "), Ok(Difficulty::Custom { min: 5, max: 9 }));
+        assert_eq!(Difficulty::parse_range("0-4294967295").map(|difficulty| difficulty.range()), Ok(0..=u32::MAX));
+
+        assert_eq!(Difficulty::parse_range("50"), Err(String::from("invalid range: 50 (expected MIN-MAX)")));
+        assert_eq!(Difficulty::parse_range("a-b"), Err(String::from("invalid range: a-b (expected MIN-MAX)")));
+        assert_eq!(Difficulty::parse_range("-5-10"), Err(String::from("invalid range: -5-10 (expected MIN-MAX)")));
+        assert_eq!(Difficulty::parse_range("1-4294967296"), Err(String::from("invalid range: 1-4294967296 (expected MIN-MAX)")));
+        assert_eq!(Difficulty::parse_range("7-7"), Err(String::from("invalid range: min (7) must be less than max (7)")));
+        assert_eq!(Difficulty::parse_range("9..=1"), Err(String::from("invalid range: min (9) must be less than max (1)")));
+    }
+
+    #[test]
+    fn from_str_reads_what_display_writes() {
+        for difficulty in [Difficulty::Easy, Difficulty::Normal, Difficulty::Hard, Difficulty::Custom { min: 3, max: 30 }] {
+            assert_eq!(difficulty.to_string().parse(), Ok(difficulty));
+        }
+
+        assert_eq!("HARD".parse(), Ok(Difficulty::Hard));
+        assert_eq!("Custom(1..=20)".parse(), Ok(Difficulty::Custom { min: 1, max: 20 }));
+        assert_eq!("custom(1-20".parse::<Difficulty>(), Err(String::from("unknown difficulty: custom(1-20 (expected easy, normal or hard)")));
+        assert_eq!("custom(20-1)".parse::<Difficulty>(), Err(String::from("invalid range: min (20) must be less than max (1)")));
+        assert_eq!("extreme".parse::<Difficulty>(), Err(String::from("unknown difficulty: extreme (expected easy, normal or hard)")));
+    }
+
+    #[test]
+    fn serde_round_trip() {
+        let difficulties = vec![Difficulty::Easy, Difficulty::Hard, Difficulty::Custom { min: 1, max: 50 }];
+        let json = serde_json::to_string(&difficulties).unwrap();
+
+        assert_eq!(json, r#"["easy","hard","custom(1-50)"]"#);
+        assert_eq!(serde_json::from_str::<Vec<Difficulty>>(&json).unwrap(), difficulties);
+
+        let err = serde_json::from_str::<Difficulty>(r#""custom(5-5)""#).unwrap_err();
+        assert_eq!(err.to_string(), "invalid range: min (5) must be less than max (5)");
+        assert!(serde_json::from_str::<Difficulty>("3").is_err());
+    }
+
+    #[test]
+    fn presets_and_default() {
+        assert_eq!(Difficulty::default(), Difficulty::Normal);
+        assert_eq!(Difficulty::Easy.range(), 1..=10);
+        assert_eq!(Difficulty::Normal.range(), 1..=100);
+        assert_eq!(Difficulty::Hard.range(), 1..=10_000);
+    }
+}
@@ -0,0 +1,109 @@
+use std::error::Error;
+use std::fmt;
+use std::num::{IntErrorKind, ParseIntError};
+use std::ops::RangeInclusive;
+
+/// Why a line of input didn't count as a guess
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum GuessError {
+    /// Nothing but whitespace
+    Empty,
+    NotANumber(String),
+    /// A whole number, but below zero
+    Negative(String),
+    /// A whole number too big to even compare
+    Overflow(String),
+    /// Not something the secret could be, for kinds of secret other than numbers
+    Invalid { input: String, reason: String },
+    OutOfRange { guess: String, min: String, max: String },
+    /// The input ran out (Ctrl-D, or the end of a piped file)
+    Eof,
+}
+
+impl GuessError {
+    pub fn out_of_range<T: fmt::Display>(guess: &T, range: &RangeInclusive<T>) -> Self {
+        GuessError::OutOfRange {
+            guess: guess.to_string(),
+            min: range.start().to_string(),
+            max: range.end().to_string(),
+        }
+    }
+}
+
+impl fmt::Display for GuessError {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        match self {
+            GuessError::Empty                          => write!(f, "You didn't guess anything, type a guess."),
+            GuessError::NotANumber(input)              => write!(f, "\"{input}\" isn't a number, try digits like 42."),
+            GuessError::Negative(input)                => write!(f, "{input} is negative, the secret number never is."),
+            GuessError::Overflow(input)                => write!(f, "{input} is way too big, the secret number is much smaller."),
+            GuessError::Invalid { input, reason }      => write!(f, "\"{input}\" {reason}"),
+            GuessError::OutOfRange { guess, min, max } => write!(f, "{guess} isn't between {min} and {max}, try again."),
+            GuessError::Eof                            => write!(f, "No more input."),
+        }
+    }
+}
+
+impl Error for GuessError {}
+
+/// Turn a line of input into a guess, explaining exactly what's wrong if it isn't one
+pub fn parse_guess(input: &str) -> Result<u32, GuessError> {
+    let input = input.trim();
+
+    if input.is_empty() {
+        return Err(GuessError::Empty);
+    }
+
+    // `u32` parsing would call these "invalid digits", which isn't much help to a player
+    if let Some(digits) = input.strip_prefix('-') {
+        if !digits.is_empty() && digits.bytes().all(|b| b.is_ascii_digit()) {
+            return Err(GuessError::Negative(input.to_string()));
+        }
+    }
+
+    input.parse().map_err(|err: ParseIntError| match err.kind() {
+        IntErrorKind::PosOverflow => GuessError::Overflow(input.to_string()),
+        _                         => GuessError::NotANumber(input.to_string()),
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn numbers_parse() {
+        assert_eq!(parse_guess(" 42\n"), Ok(42));
+        assert_eq!(parse_guess("+7"), Ok(7));
+        assert_eq!(parse_guess("4294967295"), Ok(u32::MAX));
+        assert_eq!(parse_guess("\t"), Err(GuessError::Empty));
+    }
+
+    #[test]
+    fn too_big_numbers_overflow() {
+        assert_eq!(parse_guess("4294967296"), Err(GuessError::Overflow(String::from("4294967296"))));
+        assert_eq!(parse_guess(" 99999999999999999999 "), Err(GuessError::Overflow(String::from("99999999999999999999"))));
+        assert_eq!(
+            GuessError::Overflow(String::from("4294967296")).to_string(),
+            "4294967296 is way too big, the secret number is much smaller."
+        );
+    }
+
+    #[test]
+    fn negative_numbers_are_negative() {
+        assert_eq!(parse_guess("-5"), Err(GuessError::Negative(String::from("-5"))));
+        assert_eq!(parse_guess("-99999999999999999999"), Err(GuessError::Negative(String::from("-99999999999999999999"))));
+        assert_eq!(GuessError::Negative(String::from("-5")).to_string(), "-5 is negative, the secret number never is.");
+
+        // Only whole numbers are negative, anything else with a minus just isn't a number
+        assert_eq!(parse_guess("-"), Err(GuessError::NotANumber(String::from("-"))));
+        assert_eq!(parse_guess("-5x"), Err(GuessError::NotANumber(String::from("-5x"))));
+        assert_eq!(parse_guess("--5"), Err(GuessError::NotANumber(String::from("--5"))));
+        assert_eq!(parse_guess("4.5"), Err(GuessError::NotANumber(String::from("4.5"))));
+    }
+
+    #[test]
+    fn out_of_range_shows_the_range() {
+        assert_eq!(GuessError::out_of_range(&0, &(1..=100)).to_string(), "0 isn't between 1 and 100, try again.");
+    }
+}
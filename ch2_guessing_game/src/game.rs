@@ -0,0 +1,464 @@
+use std::cmp::Ordering;
+use std::io::{self, BufRead, Write};
+use std::ops::RangeInclusive;
+use std::time::Duration;
+
+use crate::clock::{Clock, SystemClock};
+use crate::error::GuessError;
+use crate::hints::{Hint, Proximity, Trend};
+use crate::kind::Secret;
+use crate::scoring;
+
+/// What the game tells the player after a guess
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Feedback {
+    TooSmall,
+    TooBig,
+    Correct,
+}
+
+impl From<Ordering> for Feedback {
+    // `guess.cmp(&secret)` already says everything we need to know
+    fn from(ordering: Ordering) -> Self {
+        match ordering {
+            Ordering::Less    => Feedback::TooSmall,
+            Ordering::Greater => Feedback::TooBig,
+            Ordering::Equal   => Feedback::Correct,
+        }
+    }
+}
+
+impl Feedback {
+    /// The line printed back to the player, pointing them at the range in play
+    pub fn message<T: Secret>(&self, range: &RangeInclusive<T>) -> String {
+        let (min, max, noun) = (range.start(), range.end(), T::NOUN);
+
+        match self {
+            Feedback::TooSmall => format!("Too small! (the {noun} is between {min} and {max})"),
+            Feedback::TooBig   => format!("Too big! (the {noun} is between {min} and {max})"),
+            Feedback::Correct  => String::from("You win!"),
+        }
+    }
+}
+
+/// Where a round stands
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Status {
+    Playing,
+    Won,
+    /// Ran out of attempts before finding the secret
+    Lost,
+}
+
+/// A single round of the guessing game: one secret (a number, unless `T` says otherwise) and the
+/// guesses made against it
+#[derive(Debug)]
+pub struct GuessingGame<T: Secret = u32> {
+    secret: T,
+    range: RangeInclusive<T>,
+    attempts: u32,
+    invalid_attempts: u32,
+    max_attempts: Option<u32>,
+    hints: bool,
+    history: Vec<(T, Feedback)>,
+    status: Status,
+    clock: Box<dyn Clock>,
+    started: Duration,
+    finished: Option<Duration>,
+}
+
+impl<T: Secret> GuessingGame<T> {
+    pub fn new(secret: T, range: RangeInclusive<T>) -> Self {
+        GuessingGame {
+            secret,
+            range,
+            attempts: 0,
+            invalid_attempts: 0,
+            max_attempts: None,
+            hints: false,
+            history: Vec::new(),
+            status: Status::Playing,
+            clock: Box::new(SystemClock::new()),
+            started: Duration::ZERO,
+            finished: None,
+        }
+    }
+
+    /// Time the round with `clock` instead of the system clock. The round starts now, by that clock.
+    pub fn with_clock(mut self, clock: impl Clock + 'static) -> Self {
+        self.started = clock.now();
+        self.clock = Box::new(clock);
+        self
+    }
+
+    /// Limit the round to `max_attempts` guesses; running out loses the round
+    pub fn with_max_attempts(mut self, max_attempts: u32) -> Self {
+        self.max_attempts = Some(max_attempts);
+        self
+    }
+
+    /// Limit the round to the number of guesses a perfect binary search would need. Kinds of
+    /// secret without such a number are left unlimited.
+    pub fn with_optimal_attempts(self) -> Self {
+        match T::optimal_attempts(&self.range) {
+            Some(max_attempts) => self.with_max_attempts(max_attempts),
+            None               => self,
+        }
+    }
+
+    /// Show a hot/cold hint after every wrong guess
+    pub fn with_hints(mut self) -> Self {
+        self.hints = true;
+        self
+    }
+
+    pub fn secret(&self) -> &T {
+        &self.secret
+    }
+
+    /// The range the secret (and every valid guess) falls in
+    pub fn range(&self) -> &RangeInclusive<T> {
+        &self.range
+    }
+
+    /// Number of guesses made so far
+    pub fn attempts(&self) -> u32 {
+        self.attempts
+    }
+
+    /// Every counted guess so far and the feedback it got, oldest first
+    pub fn history(&self) -> &[(T, Feedback)] {
+        &self.history
+    }
+
+    /// Number of inputs rejected without being compared. These don't use up any attempts.
+    pub fn invalid_attempts(&self) -> u32 {
+        self.invalid_attempts
+    }
+
+    pub fn max_attempts(&self) -> Option<u32> {
+        self.max_attempts
+    }
+
+    /// Guesses left before the round is lost, if the round is limited
+    pub fn attempts_left(&self) -> Option<u32> {
+        self.max_attempts.map(|max| max.saturating_sub(self.attempts))
+    }
+
+    pub fn status(&self) -> Status {
+        self.status
+    }
+
+    pub fn is_won(&self) -> bool {
+        self.status == Status::Won
+    }
+
+    pub fn is_over(&self) -> bool {
+        self.status != Status::Playing
+    }
+
+    /// Time from the start of the round until it was won or lost (or until now, if still going)
+    pub fn elapsed(&self) -> Duration {
+        self.finished.unwrap_or_else(|| self.clock.now()).saturating_sub(self.started)
+    }
+
+    /// Points earned for the round, once it has been won. Losing is worth nothing, and kinds of
+    /// secret without an optimal number of attempts aren't scored.
+    pub fn score(&self) -> Option<u32> {
+        match self.status {
+            Status::Playing => None,
+            Status::Won     => T::optimal_attempts(&self.range)
+                .map(|optimal| scoring::score(optimal, self.attempts, self.elapsed())),
+            Status::Lost    => Some(0),
+        }
+    }
+
+    /// Compare a guess against the secret and record the attempt.
+    ///
+    /// Guesses outside the range are rejected (and counted as invalid) instead. Once the round is
+    /// over, guesses are still compared but no longer counted.
+    pub fn guess(&mut self, guess: T) -> Result<Feedback, GuessError> {
+        if !self.range.contains(&guess) {
+            self.invalid_attempts += 1;
+            return Err(GuessError::out_of_range(&guess, &self.range));
+        }
+
+        let feedback = Feedback::from(guess.compare(&self.secret));
+
+        if self.is_over() {
+            return Ok(feedback);
+        }
+
+        self.attempts += 1;
+        self.history.push((guess, feedback));
+
+        if feedback == Feedback::Correct {
+            self.finish(Status::Won);
+        } else if self.attempts_left() == Some(0) {
+            self.finish(Status::Lost);
+        }
+
+        Ok(feedback)
+    }
+
+    /// Parse a line of input and guess it, counting anything that doesn't parse as invalid
+    pub fn guess_str(&mut self, input: &str) -> Result<Feedback, GuessError> {
+        let guess = T::parse_guess(input).inspect_err(|_| self.invalid_attempts += 1)?;
+
+        self.guess(guess)
+    }
+
+    fn finish(&mut self, status: Status) {
+        self.status = status;
+        self.finished = Some(self.clock.now());
+    }
+
+    /// Play the game by reading guesses from `input` and writing prompts and feedback to `output`.
+    ///
+    /// Returns once the round is won or lost, or when `input` runs out of lines (in which case a
+    /// summary of the round so far is written and the secret revealed).
+    pub fn play<R: BufRead, W: Write>(&mut self, mut input: R, mut output: W) -> io::Result<()> {
+
+        // Continue prompting user until they figure it out (or run out of tries)
+        while !self.is_over() {
+
+            match self.attempts_left() {
+                Some(1)    => writeln!(output, "Please input your guess. (last attempt!)")?,
+                Some(left) => writeln!(output, "Please input your guess. ({left} attempts left)")?,
+                None       => writeln!(output, "Please input your guess.")?,
+            }
+            output.flush()?;
+
+            let mut guess = String::new();
+
+            // Process user input; nothing left to read means nobody is left to play
+            if input.read_line(&mut guess)? == 0 {
+                writeln!(output)?;
+                writeln!(output, "{}", GuessError::Eof)?;
+                writeln!(
+                    output,
+                    "You made {} guesses ({} invalid). The secret {} was {}.",
+                    self.attempts, self.invalid_attempts, T::NOUN, self.secret,
+                )?;
+                return Ok(());
+            }
+
+            // Convert user input to the secret's type
+            // Shadows previous `guess` var with a new one
+            let guess = match T::parse_guess(&guess) {
+                Ok(num) => num,
+                Err(err) => {
+                    log::debug!("rejected {:?}: {err:?}", guess.trim());
+                    self.invalid_attempts += 1;
+                    writeln!(output, "{err}")?;
+                    continue;
+                }
+            };
+
+            // Return user input
+
+            writeln!(output, "You guessed: {guess}")?;
+
+            match self.guess(guess.clone()) {
+                Ok(feedback) => {
+                    log::debug!("guess {guess}: {feedback:?} (attempt {}, {:?})", self.attempts, self.status);
+                    writeln!(output, "{}", feedback.message(&self.range))?;
+
+                    if let Some(hint) = T::hint(self).filter(|_| self.hints && feedback != Feedback::Correct) {
+                        writeln!(output, "{hint}")?;
+                    }
+                }
+                Err(err) => {
+                    log::debug!("rejected {guess}: {err:?}");
+                    writeln!(output, "{err}")?;
+                }
+            }
+        }
+
+        if self.status == Status::Lost {
+            writeln!(output, "Out of attempts! The secret {} was {}.", T::NOUN, self.secret)?;
+        }
+
+        if let Some(score) = self.score() {
+            writeln!(output, "Score: {score} ({} attempts in {:.1}s)", self.attempts, self.elapsed().as_secs_f64())?;
+        }
+
+        if self.invalid_attempts > 0 {
+            writeln!(output, "Invalid guesses: {}", self.invalid_attempts)?;
+        }
+
+        Ok(())
+    }
+}
+
+impl GuessingGame<u32> {
+    pub fn secret_number(&self) -> u32 {
+        self.secret
+    }
+
+    /// Numbers the secret could still be, going by the feedback so far
+    pub fn remaining(&self) -> RangeInclusive<u32> {
+        let (mut low, mut high) = (*self.range.start(), *self.range.end());
+
+        for &(guess, feedback) in &self.history {
+            match feedback {
+                Feedback::TooSmall => low = low.max(guess + 1),
+                Feedback::TooBig   => high = high.min(guess - 1),
+                Feedback::Correct  => return guess..=guess,
+            }
+        }
+
+        low..=high
+    }
+
+    /// How close the latest guess was, and whether it was closer than the one before
+    pub fn hint(&self) -> Option<Hint> {
+        let (latest, _) = *self.history.last()?;
+        let previous = self.history.len().checked_sub(2).map(|i| self.history[i].0);
+
+        Some(Hint {
+            proximity: Proximity::of(latest, self.secret, &self.range),
+            trend: previous.map(|previous| Trend::between(previous, latest, self.secret)),
+        })
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::io::Cursor;
+
+    use super::*;
+    use crate::clock::ManualClock;
+    use crate::secret::{FixedSource, SecretSource};
+
+    fn game(secret: u32) -> GuessingGame {
+        GuessingGame::new(FixedSource(secret).secret(1..=100), 1..=100).with_clock(ManualClock::new())
+    }
+
+    // Play with `input` as the player's lines, and return everything written back
+    fn play(game: &mut GuessingGame, input: &str) -> String {
+        let mut output = Vec::new();
+        game.play(Cursor::new(input), &mut output).unwrap();
+        String::from_utf8(output).unwrap()
+    }
+
+    #[test]
+    fn guess_compares_against_the_secret() {
+        let mut game = game(42);
+
+        assert_eq!(game.guess(10), Ok(Feedback::TooSmall));
+        assert_eq!(game.guess(90), Ok(Feedback::TooBig));
+        assert_eq!(game.guess(42), Ok(Feedback::Correct));
+        assert_eq!(game.attempts(), 3);
+        assert_eq!(game.status(), Status::Won);
+        assert_eq!(game.history(), &[(10, Feedback::TooSmall), (90, Feedback::TooBig), (42, Feedback::Correct)]);
+    }
+
+    #[test]
+    fn guesses_after_the_round_are_not_counted() {
+        let mut game = game(42);
+
+        game.guess(42).unwrap();
+
+        assert_eq!(game.guess(1), Ok(Feedback::TooSmall));
+        assert_eq!(game.attempts(), 1);
+    }
+
+    #[test]
+    fn out_of_range_guesses_are_rejected_without_using_an_attempt() {
+        let mut game = game(42).with_max_attempts(1);
+
+        assert_eq!(game.guess(0), Err(GuessError::out_of_range(&0, &(1..=100))));
+        assert_eq!(game.guess(101), Err(GuessError::out_of_range(&101, &(1..=100))));
+        assert_eq!(game.attempts(), 0);
+        assert_eq!(game.invalid_attempts(), 2);
+        assert_eq!(game.status(), Status::Playing);
+    }
+
+    #[test]
+    fn unparseable_input_is_invalid() {
+        let mut game = game(42);
+
+        assert_eq!(game.guess_str("  "), Err(GuessError::Empty));
+        assert_eq!(game.guess_str("abc"), Err(GuessError::NotANumber(String::from("abc"))));
+        assert_eq!(game.guess_str("-5"), Err(GuessError::Negative(String::from("-5"))));
+        assert_eq!(game.guess_str("42\n"), Ok(Feedback::Correct));
+        assert_eq!(game.invalid_attempts(), 3);
+    }
+
+    #[test]
+    fn running_out_of_attempts_loses() {
+        let mut game = game(42).with_max_attempts(2);
+
+        game.guess(1).unwrap();
+        assert_eq!(game.attempts_left(), Some(1));
+        game.guess(2).unwrap();
+
+        assert_eq!(game.status(), Status::Lost);
+        assert_eq!(game.score(), Some(0));
+    }
+
+    #[test]
+    fn score_depends_on_attempts_and_time() {
+        let clock = ManualClock::new();
+        let mut game = GuessingGame::new(42, 1..=100).with_clock(clock.clone());
+
+        game.guess(50).unwrap();
+        assert_eq!(game.score(), None);
+
+        clock.set(Duration::from_secs(60));
+        game.guess(42).unwrap();
+        clock.set(Duration::from_secs(600));
+
+        // 7 optimal attempts, 2 taken, and a minute halves it
+        assert_eq!(game.elapsed(), Duration::from_secs(60));
+        assert_eq!(game.score(), Some(350));
+    }
+
+    #[test]
+    fn play_until_won() {
+        let mut game = game(42);
+        let output = play(&mut game, "50\n25\n42\n7\n");
+
+        assert!(game.is_won());
+        assert_eq!(game.attempts(), 3);
+        assert!(output.contains("You guessed: 50\nToo big! (the number is between 1 and 100)\n"));
+        assert!(output.contains("You guessed: 25\nToo small! (the number is between 1 and 100)\n"));
+        assert!(output.contains("You guessed: 42\nYou win!\n"));
+        assert!(output.contains("Score: 700 (3 attempts in 0.0s)"));
+        // The line after the win is never read
+        assert!(!output.contains("You guessed: 7"));
+    }
+
+    #[test]
+    fn play_until_lost() {
+        let mut game = game(42).with_max_attempts(2);
+        let output = play(&mut game, "1\n2\n");
+
+        assert_eq!(game.status(), Status::Lost);
+        assert!(output.contains("Please input your guess. (2 attempts left)"));
+        assert!(output.contains("Please input your guess. (last attempt!)"));
+        assert!(output.contains("Out of attempts! The secret number was 42."));
+        assert!(output.contains("Score: 0"));
+    }
+
+    #[test]
+    fn play_summarises_at_the_end_of_input() {
+        let mut game = game(42);
+        let output = play(&mut game, "10\nabc\n");
+
+        assert_eq!(game.status(), Status::Playing);
+        assert!(output.ends_with("No more input.\nYou made 1 guesses (1 invalid). The secret number was 42.\n"));
+    }
+
+    #[test]
+    fn play_rejects_out_of_range_guesses() {
+        let mut game = game(42);
+        let output = play(&mut game, "500\n42\n");
+
+        assert!(game.is_won());
+        assert_eq!(game.attempts(), 1);
+        assert!(output.contains("500 isn't between 1 and 100, try again."));
+        assert!(output.contains("Invalid guesses: 1"));
+    }
+}
@@ -0,0 +1,327 @@
+use std::env;
+use std::fs::{self, OpenOptions};
+use std::io::{self, Write};
+use std::path::{Path, PathBuf};
+use std::time::{Duration, SystemTime, UNIX_EPOCH};
+
+use crate::{Date, Difficulty};
+
+/* High scores
+
+Won rounds are appended to a tab-separated file, one per line:
+
+    name    difficulty    attempts    duration_ms    timestamp    score
+
+Lines that don't parse (a half-written line, someone editing the file by hand, ...) are skipped
+when loading rather than failing the whole table, so a bad file never stops the game.
+
+*/
+
+const HEADER: &str = "# name\tdifficulty\tattempts\tduration_ms\ttimestamp\tscore";
+
+/// One won round
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct ScoreEntry {
+    pub name: String,
+    pub difficulty: Difficulty,
+    pub attempts: u32,
+    pub duration: Duration,
+    /// Seconds since the Unix epoch
+    pub timestamp: u64,
+    pub score: u32,
+}
+
+impl ScoreEntry {
+    /// An entry for a round finished just now
+    pub fn new(name: &str, difficulty: Difficulty, attempts: u32, duration: Duration, score: u32) -> Self {
+        let timestamp = SystemTime::now()
+            .duration_since(UNIX_EPOCH)
+            .map(|since| since.as_secs())
+            .unwrap_or(0);
+
+        ScoreEntry {
+            // Tabs and newlines would break the file format
+            name: name.replace(['\t', '\n', '\r'], " "),
+            difficulty,
+            attempts,
+            duration,
+            timestamp,
+            score,
+        }
+    }
+
+    fn to_line(&self) -> String {
+        format!(
+            "{}\t{}\t{}\t{}\t{}\t{}",
+            self.name,
+            self.difficulty,
+            self.attempts,
+            self.duration.as_millis(),
+            self.timestamp,
+            self.score,
+        )
+    }
+
+    fn from_line(line: &str) -> Option<ScoreEntry> {
+        let mut fields = line.split('\t');
+
+        let entry = ScoreEntry {
+            name: fields.next().filter(|name| !name.is_empty())?.to_string(),
+            difficulty: fields.next()?.parse().ok()?,
+            attempts: fields.next()?.parse().ok()?,
+            duration: Duration::from_millis(fields.next()?.parse().ok()?),
+            timestamp: fields.next()?.parse().ok()?,
+            score: fields.next()?.parse().ok()?,
+        };
+
+        // Trailing junk means the line isn't one of ours
+        if fields.next().is_some() {
+            return None;
+        }
+
+        Some(entry)
+    }
+}
+
+/// Every recorded round, backed by a file on disk
+#[derive(Debug)]
+pub struct ScoreTable {
+    path: PathBuf,
+    entries: Vec<ScoreEntry>,
+    skipped: usize,
+}
+
+impl ScoreTable {
+    /// `$XDG_DATA_HOME/ch2_guessing_game/scores.tsv`, falling back to `~/.local/share` as XDG says to
+    pub fn default_path() -> Option<PathBuf> {
+        let data_home = env::var_os("XDG_DATA_HOME")
+            .map(PathBuf::from)
+            .filter(|path| path.is_absolute())
+            .or_else(|| env::var_os("HOME").map(|home| Path::new(&home).join(".local/share")))?;
+
+        Some(data_home.join("ch2_guessing_game").join("scores.tsv"))
+    }
+
+    /// Load the table at `path`. A missing file is just an empty table.
+    pub fn load(path: impl Into<PathBuf>) -> io::Result<ScoreTable> {
+        let path = path.into();
+
+        let bytes = match fs::read(&path) {
+            Ok(bytes) => bytes,
+            Err(err) if err.kind() == io::ErrorKind::NotFound => Vec::new(),
+            Err(err) => return Err(err),
+        };
+
+        let mut entries = Vec::new();
+        let mut skipped = 0;
+
+        // Lossy, so stray bytes only cost the line they're on
+        for line in String::from_utf8_lossy(&bytes).lines() {
+            if line.trim().is_empty() || line.starts_with('#') {
+                continue;
+            }
+
+            match ScoreEntry::from_line(line) {
+                Some(entry) => entries.push(entry),
+                None        => skipped += 1,
+            }
+        }
+
+        Ok(ScoreTable { path, entries, skipped })
+    }
+
+    pub fn path(&self) -> &Path {
+        &self.path
+    }
+
+    pub fn entries(&self) -> &[ScoreEntry] {
+        &self.entries
+    }
+
+    /// How many lines couldn't be read when loading
+    pub fn skipped(&self) -> usize {
+        self.skipped
+    }
+
+    /// Add an entry and append it to the file, creating the file (and its directory) if needed
+    pub fn record(&mut self, entry: ScoreEntry) -> io::Result<()> {
+        if let Some(dir) = self.path.parent() {
+            fs::create_dir_all(dir)?;
+        }
+
+        let mut file = OpenOptions::new().create(true).append(true).open(&self.path)?;
+
+        // Start a fresh file with a header, and never glue our line onto a truncated one
+        let existing = fs::read(&self.path)?;
+        if existing.is_empty() {
+            writeln!(file, "{HEADER}")?;
+        } else if existing.last() != Some(&b'\n') {
+            writeln!(file)?;
+        }
+
+        writeln!(file, "{}", entry.to_line())?;
+
+        self.entries.push(entry);
+
+        Ok(())
+    }
+
+    /// Best rounds first (highest score, then fewest attempts, then quickest), optionally for one difficulty only
+    pub fn leaderboard(&self, difficulty: Option<Difficulty>) -> Vec<&ScoreEntry> {
+        let mut board: Vec<&ScoreEntry> = self.entries.iter()
+            .filter(|entry| difficulty.is_none_or(|difficulty| entry.difficulty == difficulty))
+            .collect();
+
+        board.sort_by(|a, b| {
+            b.score.cmp(&a.score)
+                .then(a.attempts.cmp(&b.attempts))
+                .then(a.duration.cmp(&b.duration))
+        });
+
+        board
+    }
+}
+
+/// `YYYY-MM-DD` (UTC) for a Unix timestamp, without pulling in a date crate
+pub fn format_date(timestamp: u64) -> String {
+    Date::from_days((timestamp / 86_400) as i64).to_string()
+}
+
+#[cfg(test)]
+mod tests {
+    use std::process;
+
+    use super::*;
+
+    // A directory of its own under the system temp dir, removed again when dropped
+    struct TempDir(PathBuf);
+
+    impl TempDir {
+        fn new(name: &str) -> Self {
+            let dir = env::temp_dir().join(format!("ch2_guessing_game-{}-{name}", process::id()));
+            let _ = fs::remove_dir_all(&dir);
+            fs::create_dir_all(&dir).unwrap();
+            TempDir(dir)
+        }
+    }
+
+    impl Drop for TempDir {
+        fn drop(&mut self) {
+            let _ = fs::remove_dir_all(&self.0);
+        }
+    }
+
+    fn entry(name: &str, difficulty: Difficulty, attempts: u32, millis: u64, score: u32) -> ScoreEntry {
+        ScoreEntry {
+            name: name.to_string(),
+            difficulty,
+            attempts,
+            duration: Duration::from_millis(millis),
+            timestamp: 1_700_000_000,
+            score,
+        }
+    }
+
+    #[test]
+    fn a_missing_file_is_an_empty_table() {
+        let dir = TempDir::new("missing");
+        let table = ScoreTable::load(dir.0.join("scores.tsv")).unwrap();
+
+        assert!(table.entries().is_empty());
+        assert_eq!(table.skipped(), 0);
+    }
+
+    #[test]
+    fn loading_skips_lines_that_dont_parse() {
+        let dir = TempDir::new("corrupt");
+        let path = dir.0.join("scores.tsv");
+
+        let mut contents = format!("{HEADER}\n");
+        contents += "alice\thard\t5\t12000\t1700000000\t800\n";
+        contents += "\n";
+        contents += "bob\tnightmare\t5\t12000\t1700000000\t800\n"; // unknown difficulty
+        contents += "carol\teasy\t3\n"; // cut short
+        contents += "dave\teasy\t3\t900\t1700000000\t950\textra\n"; // trailing junk
+        contents += "\teasy\t3\t900\t1700000000\t950\n"; // no name
+        contents += "erin\tcustom(1-50)\t4\t3000\t1700000000\t600\n";
+
+        // Not UTF-8, which costs the byte rather than the line
+        let mut contents = contents.into_bytes();
+        contents.extend_from_slice(b"fr\xffank\teasy\t1\t1\t1\t1\n");
+        fs::write(&path, contents).unwrap();
+
+        let table = ScoreTable::load(&path).unwrap();
+
+        assert_eq!(table.skipped(), 4);
+        assert_eq!(
+            table.entries(),
+            &[
+                entry("alice", Difficulty::Hard, 5, 12_000, 800),
+                entry("erin", Difficulty::Custom { min: 1, max: 50 }, 4, 3_000, 600),
+                ScoreEntry { timestamp: 1, ..entry("fr\u{fffd}ank", Difficulty::Easy, 1, 1, 1) },
+            ]
+        );
+    }
+
+    #[test]
+    fn recorded_entries_are_saved() {
+        let dir = TempDir::new("record");
+        let path = dir.0.join("nested").join("scores.tsv");
+
+        let mut table = ScoreTable::load(&path).unwrap();
+        table.record(entry("alice", Difficulty::Easy, 3, 4_500, 900)).unwrap();
+        table.record(ScoreEntry::new("bob\tthe\nbuilder", Difficulty::Normal, 7, Duration::from_secs(30), 500)).unwrap();
+
+        let contents = fs::read_to_string(&path).unwrap();
+        assert!(contents.starts_with(&format!("{HEADER}\nalice\teasy\t3\t4500\t1700000000\t900\nbob the builder\tnormal\t7\t30000\t")));
+
+        let reloaded = ScoreTable::load(&path).unwrap();
+        assert_eq!(reloaded.entries(), table.entries());
+        assert_eq!(reloaded.entries()[1].name, "bob the builder");
+        assert_eq!(reloaded.skipped(), 0);
+    }
+
+    #[test]
+    fn recording_after_a_truncated_line_starts_a_new_one() {
+        let dir = TempDir::new("truncated");
+        let path = dir.0.join("scores.tsv");
+        fs::write(&path, "alice\teasy\t3\t45").unwrap();
+
+        let mut table = ScoreTable::load(&path).unwrap();
+        table.record(entry("bob", Difficulty::Easy, 2, 1_000, 950)).unwrap();
+
+        let reloaded = ScoreTable::load(&path).unwrap();
+        assert_eq!(reloaded.entries(), &[entry("bob", Difficulty::Easy, 2, 1_000, 950)]);
+        assert_eq!(reloaded.skipped(), 1);
+    }
+
+    #[test]
+    fn leaderboard_is_best_first_and_filters_by_difficulty() {
+        let dir = TempDir::new("leaderboard");
+        let mut table = ScoreTable::load(dir.0.join("scores.tsv")).unwrap();
+
+        for entry in [
+            entry("slow", Difficulty::Easy, 3, 9_000, 900),
+            entry("low", Difficulty::Easy, 2, 1_000, 500),
+            entry("hard", Difficulty::Hard, 9, 20_000, 990),
+            entry("fast", Difficulty::Easy, 3, 2_000, 900),
+            entry("fewer", Difficulty::Easy, 2, 9_500, 900),
+        ] {
+            table.record(entry).unwrap();
+        }
+
+        let names = |board: Vec<&ScoreEntry>| board.iter().map(|entry| entry.name.clone()).collect::<Vec<_>>();
+
+        assert_eq!(names(table.leaderboard(None)), ["hard", "fewer", "fast", "slow", "low"]);
+        assert_eq!(names(table.leaderboard(Some(Difficulty::Easy))), ["fewer", "fast", "slow", "low"]);
+        assert!(table.leaderboard(Some(Difficulty::Normal)).is_empty());
+    }
+
+    #[test]
+    fn dates_are_utc_days() {
+        assert_eq!(format_date(0), "1970-01-01");
+        assert_eq!(format_date(1_700_000_000), "2023-11-14");
+        assert_eq!(format_date(951_782_399), "2000-02-28");
+        assert_eq!(format_date(951_782_400), "2000-02-29");
+    }
+}
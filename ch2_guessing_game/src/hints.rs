@@ -0,0 +1,134 @@
+use std::cmp::Ordering;
+use std::fmt;
+use std::ops::RangeInclusive;
+
+/// How close a guess is to the secret, relative to the size of the range
+#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord)]
+pub enum Proximity {
+    Freezing,
+    Cold,
+    Warm,
+    Hot,
+    Burning,
+}
+
+impl Proximity {
+    /// Bands are fractions of the range: within 2% is burning, 5% hot, 15% warm, 35% cold.
+    /// Off by one is always burning, however big the range.
+    pub fn of(guess: u32, secret: u32, range: &RangeInclusive<u32>) -> Proximity {
+        let distance = guess.abs_diff(secret);
+        let size = f64::from(range.end() - range.start()) + 1.0;
+        let fraction = f64::from(distance) / size;
+
+        match fraction {
+            f if f <= 0.02 || distance <= 1 => Proximity::Burning,
+            f if f <= 0.05                  => Proximity::Hot,
+            f if f <= 0.15                  => Proximity::Warm,
+            f if f <= 0.35                  => Proximity::Cold,
+            _                               => Proximity::Freezing,
+        }
+    }
+}
+
+impl fmt::Display for Proximity {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        match self {
+            Proximity::Freezing => write!(f, "Freezing"),
+            Proximity::Cold     => write!(f, "Cold"),
+            Proximity::Warm     => write!(f, "Warm"),
+            Proximity::Hot      => write!(f, "Hot"),
+            Proximity::Burning  => write!(f, "Burning"),
+        }
+    }
+}
+
+/// Whether a guess got closer to the secret than the one before it
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Trend {
+    Warmer,
+    Colder,
+    /// Just as far away, from the other side (or the same guess again)
+    Same,
+}
+
+impl Trend {
+    pub fn between(previous: u32, latest: u32, secret: u32) -> Trend {
+        match latest.abs_diff(secret).cmp(&previous.abs_diff(secret)) {
+            Ordering::Less    => Trend::Warmer,
+            Ordering::Greater => Trend::Colder,
+            Ordering::Equal   => Trend::Same,
+        }
+    }
+}
+
+/// Hot/cold hint for the latest guess
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct Hint {
+    pub proximity: Proximity,
+    /// Compared to the previous guess, if there was one
+    pub trend: Option<Trend>,
+}
+
+impl fmt::Display for Hint {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        write!(f, "{}!", self.proximity)?;
+
+        match self.trend {
+            Some(Trend::Warmer) => write!(f, " (warmer than your last guess)"),
+            Some(Trend::Colder) => write!(f, " (colder than your last guess)"),
+            Some(Trend::Same)   => write!(f, " (no closer than your last guess)"),
+            None                => Ok(()),
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn proximity_bands_include_their_edges() {
+        // 1000 numbers, so every 1% of the range is 10
+        let range = 1..=1000;
+        let of = |distance: u32| Proximity::of(500 + distance, 500, &range);
+
+        assert_eq!(of(0), Proximity::Burning);
+        assert_eq!(of(20), Proximity::Burning);
+        assert_eq!(of(21), Proximity::Hot);
+        assert_eq!(of(50), Proximity::Hot);
+        assert_eq!(of(51), Proximity::Warm);
+        assert_eq!(of(150), Proximity::Warm);
+        assert_eq!(of(151), Proximity::Cold);
+        assert_eq!(of(350), Proximity::Cold);
+        assert_eq!(of(351), Proximity::Freezing);
+        assert_eq!(Proximity::of(150, 500, &range), Proximity::Cold);
+    }
+
+    #[test]
+    fn off_by_one_is_always_burning() {
+        assert_eq!(Proximity::of(2, 1, &(1..=10)), Proximity::Burning);
+        assert_eq!(Proximity::of(3, 1, &(1..=10)), Proximity::Cold);
+        assert_eq!(Proximity::of(u32::MAX, u32::MAX - 1, &(0..=u32::MAX)), Proximity::Burning);
+        assert_eq!(Proximity::of(0, u32::MAX, &(0..=u32::MAX)), Proximity::Freezing);
+    }
+
+    #[test]
+    fn trend_compares_distances() {
+        assert_eq!(Trend::between(10, 40, 50), Trend::Warmer);
+        assert_eq!(Trend::between(40, 10, 50), Trend::Colder);
+        assert_eq!(Trend::between(40, 60, 50), Trend::Same);
+        assert_eq!(Trend::between(40, 40, 50), Trend::Same);
+        assert_eq!(Trend::between(90, 20, 50), Trend::Warmer);
+        assert_eq!(Trend::between(0, u32::MAX, 0), Trend::Colder);
+    }
+
+    #[test]
+    fn hints_read_as_sentences() {
+        let hint = |trend| Hint { proximity: Proximity::Hot, trend }.to_string();
+
+        assert_eq!(hint(None), "Hot!");
+        assert_eq!(hint(Some(Trend::Warmer)), "Hot! (warmer than your last guess)");
+        assert_eq!(hint(Some(Trend::Colder)), "Hot! (colder than your last guess)");
+        assert_eq!(hint(Some(Trend::Same)), "Hot! (no closer than your last guess)");
+    }
+}
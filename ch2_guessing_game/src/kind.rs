@@ -0,0 +1,508 @@
+use std::cmp::Ordering;
+use std::fmt;
+use std::fs;
+use std::io;
+use std::ops::RangeInclusive;
+use std::path::Path;
+use std::str::FromStr;
+
+use crate::error::{self, GuessError};
+use crate::hints::Hint;
+use crate::{scoring, GuessingGame, SecretSource};
+
+/* Kinds of secret
+
+The game works with anything that implements `Secret`: a way to compare a guess against the
+secret (for most types that's just `Ord::cmp`) and a way to read a guess from a line of input
+(usually `FromStr`). Besides plain numbers there are:
+
+- `Word`: guess the word, with "too small/too big" meaning before/after it in the dictionary
+- `Date`: guess the `YYYY-MM-DD` date
+- `Float`: guess the number to within a tolerance
+
+*/
+
+/// Something that can be the secret in a round of the guessing game
+pub trait Secret: Clone + Ord + fmt::Debug + fmt::Display + FromStr<Err: fmt::Display> + Send + 'static {
+    /// What to call the secret in messages ("the number is between ...")
+    const NOUN: &'static str;
+
+    /// How `self`, as a guess, compares to `secret`. `Equal` wins the round.
+    fn compare(&self, secret: &Self) -> Ordering;
+
+    /// Read a guess from a line of input
+    fn parse_guess(input: &str) -> Result<Self, GuessError> {
+        let input = input.trim();
+
+        if input.is_empty() {
+            return Err(GuessError::Empty);
+        }
+
+        input.parse().map_err(|err: Self::Err| GuessError::Invalid {
+            input: input.to_string(),
+            reason: err.to_string(),
+        })
+    }
+
+    /// Fewest guesses that always finds a secret in `range`, if that can be worked out from the
+    /// range alone. Rounds without it can't be limited with `--limited` or scored.
+    fn optimal_attempts(_range: &RangeInclusive<Self>) -> Option<u32> {
+        None
+    }
+
+    /// Hot/cold hint for the latest guess of a round, for kinds where distance means something
+    fn hint(_game: &GuessingGame<Self>) -> Option<Hint> {
+        None
+    }
+}
+
+impl Secret for u32 {
+    const NOUN: &'static str = "number";
+
+    fn compare(&self, secret: &Self) -> Ordering {
+        self.cmp(secret)
+    }
+
+    // The number-specific parser explains negative and too-big numbers properly
+    fn parse_guess(input: &str) -> Result<Self, GuessError> {
+        error::parse_guess(input)
+    }
+
+    fn optimal_attempts(range: &RangeInclusive<Self>) -> Option<u32> {
+        Some(scoring::optimal_attempts(range))
+    }
+
+    fn hint(game: &GuessingGame<Self>) -> Option<Hint> {
+        game.hint()
+    }
+}
+
+/// A lowercase word made of letters only
+#[derive(Debug, Clone, PartialEq, Eq, PartialOrd, Ord, Hash)]
+pub struct Word(String);
+
+impl Word {
+    pub fn as_str(&self) -> &str {
+        &self.0
+    }
+}
+
+impl FromStr for Word {
+    type Err = String;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        let word = s.trim().to_lowercase();
+
+        if word.is_empty() || !word.chars().all(char::is_alphabetic) {
+            return Err(String::from("isn't a word, use letters only."));
+        }
+
+        Ok(Word(word))
+    }
+}
+
+impl fmt::Display for Word {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        write!(f, "{}", self.0)
+    }
+}
+
+impl Secret for Word {
+    const NOUN: &'static str = "word";
+
+    fn compare(&self, secret: &Self) -> Ordering {
+        self.cmp(secret)
+    }
+}
+
+/// The words a secret word is picked from, in dictionary order
+#[derive(Debug, Clone)]
+pub struct Dictionary {
+    words: Vec<Word>,
+}
+
+impl Dictionary {
+    /// A small list of everyday words, for when there's no dictionary file
+    pub const BUILTIN: &'static [&'static str] = &[
+        "apple", "bread", "candle", "dragon", "eagle", "forest", "garden", "harbor", "island",
+        "jungle", "kettle", "ladder", "mirror", "needle", "orange", "pepper", "quartz", "rabbit",
+        "saddle", "tunnel", "umbrella", "velvet", "window", "yellow", "zebra",
+    ];
+
+    pub fn builtin() -> Self {
+        Dictionary::from_words(Dictionary::BUILTIN.iter().copied())
+    }
+
+    /// One word per line. Lines that aren't a single word are skipped.
+    pub fn load(path: impl AsRef<Path>) -> io::Result<Self> {
+        let contents = fs::read_to_string(path)?;
+        let dictionary = Dictionary::from_words(contents.lines());
+
+        if dictionary.words.len() < 2 {
+            return Err(io::Error::new(io::ErrorKind::InvalidData, "a dictionary needs at least two words"));
+        }
+
+        Ok(dictionary)
+    }
+
+    pub fn from_words<'a>(words: impl IntoIterator<Item = &'a str>) -> Self {
+        let mut words: Vec<Word> = words.into_iter().filter_map(|word| word.parse().ok()).collect();
+
+        words.sort();
+        words.dedup();
+
+        Dictionary { words }
+    }
+
+    pub fn len(&self) -> usize {
+        self.words.len()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.words.is_empty()
+    }
+
+    /// From the first word to the last
+    pub fn range(&self) -> Option<RangeInclusive<Word>> {
+        Some(self.words.first()?.clone()..=self.words.last()?.clone())
+    }
+
+    pub fn pick(&self, source: &mut dyn SecretSource) -> Option<Word> {
+        let last = u32::try_from(self.words.len().checked_sub(1)?).unwrap_or(u32::MAX);
+
+        self.words.get(source.secret(0..=last) as usize).cloned()
+    }
+}
+
+/// A calendar date, `YYYY-MM-DD`, ordered by when it is
+#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord, Hash)]
+pub struct Date {
+    year: i32,
+    month: u32,
+    day: u32,
+}
+
+impl Date {
+    pub fn new(year: i32, month: u32, day: u32) -> Option<Date> {
+        let valid = (1..=12).contains(&month) && day >= 1 && day <= Date::days_in_month(year, month);
+
+        valid.then_some(Date { year, month, day })
+    }
+
+    fn is_leap_year(year: i32) -> bool {
+        (year % 4 == 0 && year % 100 != 0) || year % 400 == 0
+    }
+
+    fn days_in_month(year: i32, month: u32) -> u32 {
+        match month {
+            2 if Date::is_leap_year(year) => 29,
+            2                             => 28,
+            4 | 6 | 9 | 11                => 30,
+            _                             => 31,
+        }
+    }
+
+    /// The date `days` days after 1970-01-01 (Howard Hinnant's days-to-civil algorithm)
+    pub fn from_days(days: i64) -> Date {
+        let days = days + 719_468;
+        let era = days.div_euclid(146_097);
+        let day_of_era = days.rem_euclid(146_097);
+        let year_of_era = (day_of_era - day_of_era / 1_460 + day_of_era / 36_524 - day_of_era / 146_096) / 365;
+        let day_of_year = day_of_era - (365 * year_of_era + year_of_era / 4 - year_of_era / 100);
+        let month_index = (5 * day_of_year + 2) / 153;
+        let day = day_of_year - (153 * month_index + 2) / 5 + 1;
+        let month = if month_index < 10 { month_index + 3 } else { month_index - 9 };
+        let year = year_of_era + era * 400 + i64::from(month <= 2);
+
+        Date { year: year as i32, month: month as u32, day: day as u32 }
+    }
+
+    /// Days since 1970-01-01 (the inverse of `from_days`)
+    pub fn to_days(&self) -> i64 {
+        let year = i64::from(self.year) - i64::from(self.month <= 2);
+        let era = year.div_euclid(400);
+        let year_of_era = year.rem_euclid(400);
+        let month = i64::from(self.month);
+        let day_of_year = (153 * (if month > 2 { month - 3 } else { month + 9 }) + 2) / 5 + i64::from(self.day) - 1;
+        let day_of_era = year_of_era * 365 + year_of_era / 4 - year_of_era / 100 + day_of_year;
+
+        era * 146_097 + day_of_era - 719_468
+    }
+
+    /// A date in `range`, picked by `source`
+    pub fn pick(range: &RangeInclusive<Date>, source: &mut dyn SecretSource) -> Date {
+        let span = range.end().to_days() - range.start().to_days();
+        let offset = source.secret(0..=u32::try_from(span).unwrap_or(u32::MAX));
+
+        Date::from_days(range.start().to_days() + i64::from(offset))
+    }
+}
+
+impl FromStr for Date {
+    type Err = String;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        let invalid = || String::from("isn't a date, use YYYY-MM-DD.");
+
+        // Exactly this many digits, so "+999" or "-001" isn't taken for a year
+        let digits = |part: &&str, len: usize| part.len() == len && part.bytes().all(|b| b.is_ascii_digit());
+
+        let mut parts = s.trim().splitn(3, '-');
+        let year = parts.next().filter(|y| digits(y, 4)).ok_or_else(invalid)?;
+        let month = parts.next().filter(|m| digits(m, 2)).ok_or_else(invalid)?;
+        let day = parts.next().filter(|d| digits(d, 2)).ok_or_else(invalid)?;
+
+        let year = year.parse().map_err(|_| invalid())?;
+        let month = month.parse().map_err(|_| invalid())?;
+        let day = day.parse().map_err(|_| invalid())?;
+
+        Date::new(year, month, day).ok_or_else(|| String::from("isn't a day on the calendar."))
+    }
+}
+
+impl fmt::Display for Date {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        write!(f, "{:04}-{:02}-{:02}", self.year, self.month, self.day)
+    }
+}
+
+impl Secret for Date {
+    const NOUN: &'static str = "date";
+
+    fn compare(&self, secret: &Self) -> Ordering {
+        self.cmp(secret)
+    }
+
+    fn optimal_attempts(range: &RangeInclusive<Self>) -> Option<u32> {
+        let days = range.end().to_days() - range.start().to_days() + 1;
+
+        Some(scoring::optimal_attempts_for(u64::try_from(days).ok()?))
+    }
+}
+
+/// A decimal number that counts as guessed when a guess lands within `tolerance` of it.
+///
+/// The tolerance belongs to the secret (and the ends of the range); guesses read from input have
+/// none. Ordering ignores the tolerance, which only comes into play in `Secret::compare`.
+#[derive(Debug, Clone, Copy)]
+pub struct Float {
+    value: f64,
+    tolerance: f64,
+}
+
+impl Float {
+    pub fn new(value: f64, tolerance: f64) -> Self {
+        Float { value, tolerance: tolerance.abs() }
+    }
+
+    pub fn value(&self) -> f64 {
+        self.value
+    }
+
+    // Enough decimal places to show the tolerance, e.g. 2 for 0.01
+    fn decimals(&self) -> usize {
+        if self.tolerance <= 0.0 {
+            return 0;
+        }
+
+        (-self.tolerance.log10()).ceil().clamp(0.0, 10.0) as usize
+    }
+
+    /// A number in `range` picked by `source`, on a grid `tolerance` apart, with the tolerance of
+    /// the range's start. The grid keeps the secret from being a long string of digits.
+    pub fn pick(range: &RangeInclusive<Float>, source: &mut dyn SecretSource) -> Float {
+        let (start, end) = (range.start(), range.end());
+        let tolerance = start.tolerance;
+
+        let steps = if tolerance > 0.0 { ((end.value - start.value) / tolerance).floor() } else { 0.0 };
+        let step = source.secret(0..=steps.min(f64::from(u32::MAX)) as u32);
+
+        let scale = 10f64.powi(start.decimals() as i32);
+        let value = ((start.value + f64::from(step) * tolerance) * scale).round() / scale;
+
+        Float::new(value, tolerance)
+    }
+}
+
+impl PartialEq for Float {
+    fn eq(&self, other: &Self) -> bool {
+        self.value.total_cmp(&other.value) == Ordering::Equal
+    }
+}
+
+impl Eq for Float {}
+
+impl PartialOrd for Float {
+    fn partial_cmp(&self, other: &Self) -> Option<Ordering> {
+        Some(self.cmp(other))
+    }
+}
+
+impl Ord for Float {
+    fn cmp(&self, other: &Self) -> Ordering {
+        self.value.total_cmp(&other.value)
+    }
+}
+
+impl FromStr for Float {
+    type Err = String;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        match s.trim().parse::<f64>() {
+            Ok(value) if value.is_finite() => Ok(Float::new(value, 0.0)),
+            _                              => Err(String::from("isn't a number, try something like 3.14.")),
+        }
+    }
+}
+
+impl fmt::Display for Float {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        if self.tolerance > 0.0 {
+            write!(f, "{:.*}", self.decimals(), self.value)
+        } else {
+            write!(f, "{}", self.value)
+        }
+    }
+}
+
+impl Secret for Float {
+    const NOUN: &'static str = "number";
+
+    // Decimals like 0.99 aren't exact in binary, so a guess right on the edge of the tolerance can
+    // land a hair outside it; the slack lets it count
+    fn compare(&self, secret: &Self) -> Ordering {
+        if (self.value - secret.value).abs() <= secret.tolerance * (1.0 + 1e-9) {
+            return Ordering::Equal;
+        }
+
+        self.value.total_cmp(&secret.value)
+    }
+
+    // Every guess within the tolerance is a win, so it's a binary search over buckets twice as wide
+    fn optimal_attempts(range: &RangeInclusive<Self>) -> Option<u32> {
+        let tolerance = range.start().tolerance;
+
+        if tolerance <= 0.0 {
+            return None;
+        }
+
+        let buckets = ((range.end().value - range.start().value) / (2.0 * tolerance)).ceil().max(1.0);
+
+        Some(scoring::optimal_attempts_for(buckets as u64))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::secret::FixedSource;
+
+    fn date(s: &str) -> Date {
+        s.parse().unwrap()
+    }
+
+    #[test]
+    fn words_are_lowercase_letters() {
+        assert_eq!("  Apple ".parse::<Word>().unwrap().as_str(), "apple");
+        assert!("".parse::<Word>().is_err());
+        assert!("two words".parse::<Word>().is_err());
+        assert!("r2d2".parse::<Word>().is_err());
+        assert_eq!(Word::parse_guess(" "), Err(GuessError::Empty));
+        assert_eq!("apple".parse::<Word>().unwrap().compare(&"bread".parse().unwrap()), Ordering::Less);
+    }
+
+    #[test]
+    fn dictionaries_are_sorted_without_duplicates() {
+        let dictionary = Dictionary::from_words(["pear", "Apple", "fig", "apple", "not a word", "", "pear"]);
+
+        assert_eq!(dictionary.len(), 3);
+        assert_eq!(dictionary.range(), Some(date_words("apple", "pear")));
+        assert_eq!(dictionary.pick(&mut FixedSource(1)), Some(Word(String::from("fig"))));
+        assert_eq!(dictionary.pick(&mut FixedSource(3)), None);
+
+        assert!(Dictionary::from_words([]).is_empty());
+        assert_eq!(Dictionary::from_words([]).range(), None);
+        assert_eq!(Dictionary::builtin().len(), Dictionary::BUILTIN.len());
+    }
+
+    fn date_words(first: &str, last: &str) -> RangeInclusive<Word> {
+        Word(String::from(first))..=Word(String::from(last))
+    }
+
+    #[test]
+    fn days_round_trip() {
+        assert_eq!(Date::from_days(0), date("1970-01-01"));
+        assert_eq!(date("2000-03-01").to_days(), 11_017);
+        assert_eq!(Date::from_days(-1), date("1969-12-31"));
+
+        for days in (-800_000..800_000).step_by(997) {
+            assert_eq!(Date::from_days(days).to_days(), days);
+        }
+    }
+
+    #[test]
+    fn leap_days() {
+        assert_eq!(Date::new(2024, 2, 29), Some(date("2024-02-29")));
+        assert_eq!(Date::new(2000, 2, 29), Some(date("2000-02-29")));
+        assert_eq!(Date::new(1900, 2, 29), None);
+        assert_eq!(Date::new(2023, 2, 29), None);
+
+        assert_eq!(date("2024-02-28").to_days() + 1, date("2024-02-29").to_days());
+        assert_eq!(Date::from_days(date("2024-02-29").to_days() + 1), date("2024-03-01"));
+        assert_eq!(Date::from_days(date("2023-02-28").to_days() + 1), date("2023-03-01"));
+    }
+
+    #[test]
+    fn dates_must_be_yyyy_mm_dd() {
+        let not_a_date = Err(String::from("isn't a date, use YYYY-MM-DD."));
+
+        for input in ["", "2024", "2024-1-05", "24-01-05", "2024/01/05", "+999-01-01", "-001-01-01", "2024-+1-05", "2024-01-+5", "2024-01-05x"] {
+            assert_eq!(input.parse::<Date>(), not_a_date, "{input:?}");
+        }
+
+        assert_eq!("2024-04-31".parse::<Date>(), Err(String::from("isn't a day on the calendar.")));
+        assert_eq!("2024-13-01".parse::<Date>(), Err(String::from("isn't a day on the calendar.")));
+        assert_eq!(" 0999-01-01 ".parse::<Date>().unwrap().to_string(), "0999-01-01");
+    }
+
+    #[test]
+    fn dates_pick_within_the_range() {
+        let range = date("2024-01-01")..=date("2024-12-31");
+
+        assert_eq!(Date::pick(&range, &mut FixedSource(0)), date("2024-01-01"));
+        assert_eq!(Date::pick(&range, &mut FixedSource(365)), date("2024-12-31"));
+        assert_eq!(Date::optimal_attempts(&range), Some(9));
+    }
+
+    #[test]
+    fn floats_win_within_the_tolerance() {
+        let range = Float::new(1.0, 0.01)..=Float::new(10.0, 0.01);
+        let lowest = Float::pick(&range, &mut FixedSource(0));
+        let highest = Float::pick(&range, &mut FixedSource(900));
+
+        assert_eq!(lowest.value(), 1.0);
+        assert_eq!(highest.value(), 10.0);
+
+        let guess = |input: &str| Float::parse_guess(input).unwrap();
+
+        assert_eq!(guess("0.99").compare(&lowest), Ordering::Equal);
+        assert_eq!(guess("1.01").compare(&lowest), Ordering::Equal);
+        assert_eq!(guess("0.98").compare(&lowest), Ordering::Less);
+        assert_eq!(guess("9.99").compare(&highest), Ordering::Equal);
+        assert_eq!(guess("10.01").compare(&highest), Ordering::Equal);
+        assert_eq!(guess("10.02").compare(&highest), Ordering::Greater);
+    }
+
+    #[test]
+    fn floats_without_a_tolerance_must_be_exact() {
+        let secret = Float::new(2.5, 0.0);
+
+        assert_eq!(Float::new(2.5, 0.0).compare(&secret), Ordering::Equal);
+        assert_eq!(Float::new(2.500001, 0.0).compare(&secret), Ordering::Greater);
+        assert_eq!(Float::optimal_attempts(&(secret..=Float::new(3.0, 0.0))), None);
+        assert!("inf".parse::<Float>().is_err());
+        assert!("NaN".parse::<Float>().is_err());
+        assert_eq!(Float::new(1.23456, 0.01).to_string(), "1.23");
+        assert_eq!(secret.to_string(), "2.5");
+    }
+}
@@ -0,0 +1,38 @@
+/* Guessing Game
+
+The game logic lives in this library so it can be driven by anything that can hand it lines of
+input and somewhere to write output, not just a terminal. `main.rs` wires it up to stdin/stdout.
+
+*/
+
+pub mod bench;
+pub mod clock;
+pub mod difficulty;
+pub mod error;
+pub mod game;
+pub mod highscores;
+pub mod hints;
+pub mod kind;
+pub mod mastermind;
+pub mod reverse;
+pub mod scoring;
+pub mod secret;
+pub mod server;
+pub mod solver;
+pub mod transcript;
+pub mod tui;
+
+pub use bench::{Bench, Stats};
+pub use clock::{Clock, ManualClock, SystemClock};
+pub use difficulty::Difficulty;
+pub use error::{parse_guess, GuessError};
+pub use game::{Feedback, GuessingGame, Status};
+pub use highscores::{ScoreEntry, ScoreTable};
+pub use hints::{Hint, Proximity, Trend};
+pub use kind::{Date, Dictionary, Float, Secret, Word};
+pub use mastermind::{Code, KnuthSolver, Mastermind};
+pub use reverse::ReverseGame;
+pub use secret::{FixedSource, SecretSource, SeededSource};
+pub use server::Server;
+pub use solver::{LyingOracle, Strategy};
+pub use transcript::{Recorder, Settings};
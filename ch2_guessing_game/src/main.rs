@@ -1,51 +1,386 @@
-use std::io;
-use std::cmp::Ordering;
-use rand::Rng;
+mod cli;
 
-fn main() {
+use std::env;
+use std::io::{self, BufRead, IsTerminal, Write};
+use std::net::TcpListener;
+use std::ops::RangeInclusive;
+use std::path::PathBuf;
+use std::process;
+
+use clap::{CommandFactory, Parser};
+use log::{debug, error, info, warn};
+
+use ch2_guessing_game::highscores::format_date;
+use ch2_guessing_game::scoring::optimal_attempts;
+use ch2_guessing_game::solver::{strategy_by_name, STRATEGIES};
+use ch2_guessing_game::transcript::{self, load_events};
+use ch2_guessing_game::tui;
+use ch2_guessing_game::{
+    Bench, Code, Date, Dictionary, Difficulty, Float, GuessingGame, Recorder, ReverseGame, ScoreEntry, ScoreTable, Secret,
+    KnuthSolver, Mastermind, SeededSource, Server, Settings, Status,
+};
+
+use cli::{BenchArgs, Cli, Command, Kind, MastermindArgs, PlayArgs, RangeArgs, ReplayArgs, ReverseArgs, ScoresArgs, ServeArgs};
+
+// `--scores-file` if given, otherwise the XDG data dir
+fn scores_path(scores_file: Option<PathBuf>) -> Option<PathBuf> {
+    scores_file.or_else(ScoreTable::default_path)
+}
+
+fn print_scores(args: ScoresArgs) {
+    let Some(path) = scores_path(args.scores_file) else {
+        error!("couldn't find a place to keep scores (is $HOME set?)");
+        process::exit(1);
+    };
+
+    let table = ScoreTable::load(&path).unwrap_or_else(|err| {
+        error!("couldn't read scores from {}: {err}", path.display());
+        process::exit(1);
+    });
+
+    if table.skipped() > 0 {
+        warn!("skipped {} unreadable line(s) in {}", table.skipped(), path.display());
+    }
+
+    let board = table.leaderboard(args.difficulty);
+
+    if board.is_empty() {
+        println!("No scores yet.");
+        return;
+    }
+
+    println!("{:>4}  {:<16} {:<18} {:>8} {:>9} {:>6}  date", "#", "name", "difficulty", "attempts", "time", "score");
+
+    for (rank, entry) in board.iter().take(args.limit).enumerate() {
+        println!(
+            "{:>4}  {:<16} {:<18} {:>8} {:>8.1}s {:>6}  {}",
+            rank + 1,
+            entry.name,
+            entry.difficulty.to_string(),
+            entry.attempts,
+            entry.duration.as_secs_f64(),
+            entry.score,
+            format_date(entry.timestamp),
+        );
+    }
+}
+
+// Combinations clap can't check on its own (like `--min 5 --max 5`) still get a clap-style error
+fn difficulty(args: &RangeArgs) -> Difficulty {
+    args.difficulty().unwrap_or_else(|err| {
+        Cli::command().error(clap::error::ErrorKind::ValueValidation, err).exit()
+    })
+}
+
+fn reverse(args: ReverseArgs) {
+    let difficulty = difficulty(&args.range);
+    let range = difficulty.range();
+
+    println!("Think of a number between {} and {}, and I'll guess it! ({difficulty})", range.start(), range.end());
+
+    let mut game = ReverseGame::new(range);
+
+    game.play(io::stdin().lock(), io::stdout().lock())
+        .expect("Failed to play the game");
+}
+
+fn bench(args: BenchArgs) {
+    if !(0.0..=1.0).contains(&args.lie_rate) {
+        Cli::command().error(clap::error::ErrorKind::ValueValidation, "--lie-rate must be between 0 and 1").exit()
+    }
+
+    let difficulty = difficulty(&args.range);
+
+    let mut bench = Bench::new(difficulty.range(), args.games, args.seed);
+    bench.lie_rate = args.lie_rate;
+
+    let names: Vec<&str> = if args.strategy.is_empty() {
+        STRATEGIES.to_vec()
+    } else {
+        args.strategy.iter().map(String::as_str).collect()
+    };
+
+    println!("{} games per strategy, {difficulty}, seed {}, lie rate {}", args.games, args.seed, args.lie_rate);
+
+    for name in names {
+        let mut strategy = strategy_by_name(name, args.seed.wrapping_add(2), args.max_lies)
+            .expect("clap only accepts known strategies");
+
+        info!("running {name}");
+
+        println!();
+        bench.run(strategy.as_mut()).write_report(io::stdout().lock())
+            .expect("Failed to write the report");
+    }
+}
+
+fn serve(args: ServeArgs) {
+    let difficulty = difficulty(&args.range);
+
+    let source = match args.seed {
+        Some(seed) => SeededSource::new(seed),
+        None       => SeededSource::random(),
+    };
+
+    info!("seed {}, difficulty {difficulty}", source.seed());
+
+    let listener = TcpListener::bind((args.bind.as_str(), args.port)).unwrap_or_else(|err| {
+        error!("couldn't listen on {}:{}: {err}", args.bind, args.port);
+        process::exit(1);
+    });
+
+    match listener.local_addr() {
+        Ok(addr) => println!("Serving the guessing game on {addr} ({difficulty})"),
+        Err(_)   => println!("Serving the guessing game ({difficulty})"),
+    }
+
+    let server = Server::new(difficulty.range(), source);
+
+    if let Err(err) = server.serve(listener) {
+        error!("server stopped: {err}");
+        process::exit(1);
+    }
+}
+
+// Prompt the user for input, and play until the round is over
+fn play_round(game: &mut GuessingGame, settings: &Settings, input: impl BufRead, mut output: impl Write) -> io::Result<()> {
+    writeln!(output, "{}", settings.greeting())?;
+
+    game.play(input, output)
+}
+
+// The full-screen game is gone once it's over, so leave the result behind on the terminal
+fn print_summary(game: &GuessingGame) {
+    match (game.status(), game.score()) {
+        (Status::Won, Some(score)) => {
+            println!("You win! Score: {score} ({} attempts in {:.1}s)", game.attempts(), game.elapsed().as_secs_f64());
+        }
+        (Status::Lost, _) => println!("Out of attempts! The secret number was {}.", game.secret_number()),
+        _                 => println!("The secret number was {}.", game.secret_number()),
+    }
+}
+
+fn replay(args: ReplayArgs) {
+    let events = load_events(&args.file).unwrap_or_else(|err| {
+        error!("{}: {err}", args.file.display());
+        process::exit(1);
+    });
+
+    match transcript::replay(&events) {
+        Ok((settings, matched)) => {
+            println!("{}: {matched} lines match (seed {}, {})", args.file.display(), settings.seed, settings.difficulty);
+        }
+        Err(err) => {
+            error!("{}: replay differs from the transcript, {err}", args.file.display());
+            process::exit(1);
+        }
+    }
+}
 
-    let debug: bool = false;
+fn mastermind(args: MastermindArgs) {
+    let seed = args.seed.unwrap_or_else(|| SeededSource::random().seed());
+    let secret = Code::random(&mut SeededSource::new(seed));
 
-    // Prompt the user for input
+    info!("seed {seed}");
+    debug!("the secret code is {secret}");
 
-    println!("Guess the number!");
+    let mut game = Mastermind::new(secret);
 
-    let secret_number = rand::thread_rng().gen_range(1..=100);
+    if let Some(max_attempts) = args.attempts {
+        game = game.with_max_attempts(max_attempts);
+    }
+
+    if !args.solve {
+        println!("Crack the code! It's 4 different digits, like 0123.");
+        println!("Bulls are right digits in the right place, cows are right digits in the wrong place.");
 
-    if debug {
-        println!("The secret number is: {secret_number}");
+        return game.play(io::stdin().lock(), io::stdout().lock())
+            .expect("Failed to play the game");
     }
 
-    // Continue prompting user until they figure it out
-    loop {
+    println!("Cracking {secret} (seed {seed})");
 
-        println!("Please input your guess.");
+    let won = KnuthSolver::new().solve(&mut game, |guess, score, left| {
+        let plural = if left == 1 { "" } else { "s" };
+        println!("{guess}: {score} ({left} code{plural} left)");
+    });
 
-        let mut guess = String::new();
+    if won {
+        println!("Cracked in {} guesses", game.attempts());
+    } else {
+        println!("Out of attempts!");
+    }
+}
 
-        // Process user input
+// Words, dates and floats get the plain line-based game, without the extras numbers have
+fn play_secret<T: Secret>(secret: T, range: RangeInclusive<T>, args: &PlayArgs) {
+    if args.hints || args.tui || args.record.is_some() {
+        warn!("--hints, --tui and --record only work with numbers, ignoring them");
+    }
 
-        io::stdin().read_line(&mut guess)
-            .expect("Failed to read line");
+    let mut game = GuessingGame::new(secret, range);
+
+    debug!("the secret {} is {}", T::NOUN, game.secret());
+
+    if let Some(max_attempts) = args.attempts {
+        game = game.with_max_attempts(max_attempts);
+    } else if args.limited {
+        game = game.with_optimal_attempts();
+    }
 
-        // Convert user input to u32
-        // Shadows previous `guess` var with a new one
-        let guess: u32 = match guess.trim().parse() {
-            Ok(num) => num,
-            Err(_)  => continue,
-        };
+    println!("Guess the {} between {} and {}!", T::NOUN, game.range().start(), game.range().end());
 
-        // Return user input
+    game.play(io::stdin().lock(), io::stdout().lock())
+        .expect("Failed to play the game");
+}
 
-        println!("You guessed: {guess}");
+fn play_kind(args: PlayArgs) {
+    // Drawn once for the whole session, so the seed logged is the one that replays it
+    let seed = args.seed.unwrap_or_else(|| SeededSource::random().seed());
+    let mut source = SeededSource::new(seed);
 
-        match guess.cmp(&secret_number) {
-            Ordering::Less    => println!("Too small!"),
-            Ordering::Greater => println!("Too big!"),
-            Ordering::Equal   => {
-                println!("You win!");
-                break;
+    info!("seed {seed}, kind {:?}", args.kind);
+
+    match args.kind {
+        Kind::Number => play(args, seed),
+        Kind::Word => {
+            let dictionary = match &args.dictionary {
+                Some(path) => Dictionary::load(path).unwrap_or_else(|err| {
+                    error!("{}: {err}", path.display());
+                    process::exit(1);
+                }),
+                None => Dictionary::builtin(),
+            };
+
+            info!("{} words in the dictionary", dictionary.len());
+
+            let range = dictionary.range().expect("dictionaries have at least two words");
+            let secret = dictionary.pick(&mut source).expect("dictionaries have at least two words");
+
+            play_secret(secret, range, &args);
+        }
+        Kind::Date => {
+            if args.from > args.to {
+                Cli::command().error(clap::error::ErrorKind::ValueValidation, "--from must not be after --to").exit()
             }
+
+            let range = args.from..=args.to;
+            let secret = Date::pick(&range, &mut source);
+
+            play_secret(secret, range, &args);
+        }
+        Kind::Float => {
+            if !(args.tolerance.is_finite() && args.tolerance > 0.0) {
+                Cli::command().error(clap::error::ErrorKind::ValueValidation, "--tolerance must be above 0").exit()
+            }
+
+            let range = difficulty(&args.range).range();
+            let range = Float::new(f64::from(*range.start()), args.tolerance)..=Float::new(f64::from(*range.end()), args.tolerance);
+            let secret = Float::pick(&range, &mut source);
+
+            play_secret(secret, range, &args);
         }
     }
-} 
+}
+
+fn play(args: PlayArgs, seed: u64) {
+    let difficulty = difficulty(&args.range);
+
+    // `--attempts` picks the budget, `--limited` alone uses the optimal one
+    let max_attempts = args.attempts
+        .or_else(|| args.limited.then(|| optimal_attempts(&difficulty.range())));
+
+    let settings = Settings {
+        seed,
+        difficulty,
+        max_attempts,
+        hints: args.hints,
+    };
+
+    info!("difficulty {difficulty}");
+
+    if let Some(max_attempts) = max_attempts {
+        info!("limited to {max_attempts} attempts");
+    }
+
+    let mut game = settings.new_game();
+
+    debug!("the secret number is {}", game.secret_number());
+
+    // The TUI needs a real terminal; pipes and redirects get the line-based game
+    let tui = args.tui && io::stdin().is_terminal() && io::stdout().is_terminal();
+
+    if args.tui && !tui {
+        warn!("not a terminal, playing the line-based game instead");
+    }
+
+    let result = match &args.record {
+        _ if tui => tui::run(&mut game).map(|()| print_summary(&game)),
+        Some(path) => {
+            let recorder = Recorder::new(settings);
+            game = game.with_clock(recorder.clock());
+
+            let result = play_round(&mut game, &settings, recorder.input(io::stdin().lock()), recorder.output(io::stdout().lock()));
+
+            match recorder.save(path) {
+                Ok(())   => info!("transcript saved to {}", path.display()),
+                Err(err) => warn!("couldn't save the transcript to {}: {err}", path.display()),
+            }
+
+            result
+        }
+        None => play_round(&mut game, &settings, io::stdin().lock(), io::stdout().lock()),
+    };
+
+    result.expect("Failed to play the game");
+
+    if !game.is_won() {
+        return;
+    }
+
+    // A lost high score shouldn't take the win away, so problems here are only warnings
+    let name = args.name
+        .or_else(|| env::var("USER").ok())
+        .unwrap_or_else(|| String::from("anonymous"));
+
+    let entry = ScoreEntry::new(
+        &name,
+        difficulty,
+        game.attempts(),
+        game.elapsed(),
+        game.score().unwrap_or(0),
+    );
+
+    let Some(path) = scores_path(args.scores_file) else {
+        warn!("couldn't find a place to keep scores (is $HOME set?)");
+        return;
+    };
+
+    let result = ScoreTable::load(&path).and_then(|mut table| table.record(entry));
+
+    match result {
+        Ok(())   => info!("score saved to {}", path.display()),
+        Err(err) => warn!("couldn't save your score to {}: {err}", path.display()),
+    }
+}
+
+fn main() {
+    let cli = Cli::parse();
+
+    // Diagnostics go to stderr so stdout stays clean for scripting
+    env_logger::Builder::new()
+        .filter_level(cli.verbosity.level())
+        .format_timestamp(None)
+        .format_target(false)
+        .init();
+
+    match cli.command {
+        Some(Command::Scores(args))     => print_scores(args),
+        Some(Command::Reverse(args))    => reverse(args),
+        Some(Command::Bench(args))      => bench(args),
+        Some(Command::Serve(args))      => serve(args),
+        Some(Command::Replay(args))     => replay(args),
+        Some(Command::Mastermind(args)) => mastermind(args),
+        None                            => play_kind(cli.play),
+    }
+}
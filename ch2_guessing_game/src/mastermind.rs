@@ -0,0 +1,469 @@
+use std::fmt;
+use std::io::{self, BufRead, Write};
+use std::str::FromStr;
+
+use crate::{GuessError, SecretSource};
+
+/* Bulls and Cows
+
+A Mastermind-style variant of the game. The secret is a code of 4 different digits, and instead of
+"too small" or "too big" every guess is answered with how many digits are right and in the right
+place (bulls) and how many are right but in the wrong place (cows). 4 bulls wins.
+
+`KnuthSolver` plays it with Knuth's minimax algorithm: keep every code that agrees with the answers
+so far, and guess whichever code leaves the fewest of them in the worst case.
+
+*/
+
+/// Number of digits in a code
+pub const LENGTH: usize = 4;
+
+/// A code of `LENGTH` different digits
+#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord, Hash)]
+pub struct Code([u8; LENGTH]);
+
+impl Code {
+    pub fn new(digits: [u8; LENGTH]) -> Option<Code> {
+        let code = Code(digits);
+        let valid = digits.iter().all(|&digit| digit < 10) && code.mask().count_ones() as usize == LENGTH;
+
+        valid.then_some(code)
+    }
+
+    /// Every possible code (5040 of them), in order
+    pub fn all() -> Vec<Code> {
+        (0..10_000)
+            .filter_map(|n: u32| {
+                let digits = [n / 1000, n / 100 % 10, n / 10 % 10, n % 10].map(|digit| digit as u8);
+                Code::new(digits)
+            })
+            .collect()
+    }
+
+    /// A code picked by `source`
+    pub fn random(source: &mut dyn SecretSource) -> Code {
+        let codes = Code::all();
+
+        codes[source.secret(0..=codes.len() as u32 - 1) as usize]
+    }
+
+    pub fn digits(&self) -> [u8; LENGTH] {
+        self.0
+    }
+
+    // One bit per digit in the code
+    fn mask(&self) -> u16 {
+        self.0.iter().fold(0, |mask, &digit| mask | 1 << digit)
+    }
+
+    /// The answer to `self` as a guess against `secret` (the same either way round)
+    pub fn score(&self, secret: &Code) -> Score {
+        let bulls = self.0.iter().zip(&secret.0).filter(|(a, b)| a == b).count() as u8;
+        let common = (self.mask() & secret.mask()).count_ones() as u8;
+
+        Score { bulls, cows: common - bulls }
+    }
+}
+
+impl FromStr for Code {
+    type Err = GuessError;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        let input = s.trim();
+
+        if input.is_empty() {
+            return Err(GuessError::Empty);
+        }
+
+        let invalid = |reason: &str| GuessError::Invalid {
+            input: input.to_string(),
+            reason: reason.to_string(),
+        };
+
+        let digits: Vec<u8> = input
+            .chars()
+            .map(|c| c.to_digit(10).map(|digit| digit as u8))
+            .collect::<Option<_>>()
+            .ok_or_else(|| invalid("isn't a code, use digits only."))?;
+
+        let digits: [u8; LENGTH] = digits.try_into().map_err(|_| invalid("isn't 4 digits long."))?;
+
+        Code::new(digits).ok_or_else(|| invalid("has a repeated digit, every digit must be different."))
+    }
+}
+
+impl fmt::Display for Code {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        for digit in self.0 {
+            write!(f, "{digit}")?;
+        }
+
+        Ok(())
+    }
+}
+
+/// The answer to a guess
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub struct Score {
+    /// Right digit in the right place
+    pub bulls: u8,
+    /// Right digit in the wrong place
+    pub cows: u8,
+}
+
+impl Score {
+    pub fn is_win(&self) -> bool {
+        self.bulls as usize == LENGTH
+    }
+
+    // A different number for every possible score, to count them in an array
+    fn index(&self) -> usize {
+        self.bulls as usize * (LENGTH + 1) + self.cows as usize
+    }
+}
+
+impl fmt::Display for Score {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        let plural = |n: u8| if n == 1 { "" } else { "s" };
+
+        write!(f, "{} bull{}, {} cow{}", self.bulls, plural(self.bulls), self.cows, plural(self.cows))
+    }
+}
+
+/// A round of Bulls and Cows: one secret code and the guesses made against it
+#[derive(Debug)]
+pub struct Mastermind {
+    secret: Code,
+    max_attempts: Option<u32>,
+    history: Vec<(Code, Score)>,
+    invalid_attempts: u32,
+}
+
+impl Mastermind {
+    pub fn new(secret: Code) -> Self {
+        Mastermind {
+            secret,
+            max_attempts: None,
+            history: Vec::new(),
+            invalid_attempts: 0,
+        }
+    }
+
+    /// Limit the round to `max_attempts` guesses; running out loses the round
+    pub fn with_max_attempts(mut self, max_attempts: u32) -> Self {
+        self.max_attempts = Some(max_attempts);
+        self
+    }
+
+    pub fn secret(&self) -> Code {
+        self.secret
+    }
+
+    /// Every guess so far and the answer it got, oldest first
+    pub fn history(&self) -> &[(Code, Score)] {
+        &self.history
+    }
+
+    pub fn attempts(&self) -> u32 {
+        self.history.len() as u32
+    }
+
+    pub fn attempts_left(&self) -> Option<u32> {
+        self.max_attempts.map(|max| max.saturating_sub(self.attempts()))
+    }
+
+    pub fn is_won(&self) -> bool {
+        self.history.last().is_some_and(|(_, score)| score.is_win())
+    }
+
+    pub fn is_over(&self) -> bool {
+        self.is_won() || self.attempts_left() == Some(0)
+    }
+
+    /// Score a guess against the secret and record it. Once the round is over, guesses are
+    /// still scored but no longer counted.
+    pub fn guess(&mut self, guess: Code) -> Score {
+        let score = guess.score(&self.secret);
+
+        if !self.is_over() {
+            self.history.push((guess, score));
+        }
+
+        score
+    }
+
+    /// Play the game by reading guesses from `input` and writing prompts and answers to `output`
+    pub fn play<R: BufRead, W: Write>(&mut self, mut input: R, mut output: W) -> io::Result<()> {
+
+        while !self.is_over() {
+
+            match self.attempts_left() {
+                Some(1)    => writeln!(output, "Please input your guess. (last attempt!)")?,
+                Some(left) => writeln!(output, "Please input your guess. ({left} attempts left)")?,
+                None       => writeln!(output, "Please input your guess.")?,
+            }
+            output.flush()?;
+
+            let mut guess = String::new();
+
+            if input.read_line(&mut guess)? == 0 {
+                writeln!(output)?;
+                writeln!(output, "{}", GuessError::Eof)?;
+                writeln!(output, "You made {} guesses. The secret code was {}.", self.attempts(), self.secret)?;
+                return Ok(());
+            }
+
+            let guess: Code = match guess.parse() {
+                Ok(code) => code,
+                Err(err) => {
+                    log::debug!("rejected {:?}: {err:?}", guess.trim());
+                    self.invalid_attempts += 1;
+                    writeln!(output, "{err}")?;
+                    continue;
+                }
+            };
+
+            let score = self.guess(guess);
+
+            log::debug!("guess {guess}: {score} (attempt {})", self.attempts());
+
+            if score.is_win() {
+                writeln!(output, "{guess}: You win! ({} attempts)", self.attempts())?;
+            } else {
+                writeln!(output, "{guess}: {score}")?;
+            }
+        }
+
+        if !self.is_won() {
+            writeln!(output, "Out of attempts! The secret code was {}.", self.secret)?;
+        }
+
+        if self.invalid_attempts > 0 {
+            writeln!(output, "Invalid guesses: {}", self.invalid_attempts)?;
+        }
+
+        Ok(())
+    }
+}
+
+/// Knuth's minimax solver. Never takes more than 7 guesses for 4 different digits.
+#[derive(Debug)]
+pub struct KnuthSolver {
+    all: Vec<Code>,
+    /// Codes that agree with every answer so far
+    candidates: Vec<Code>,
+}
+
+impl Default for KnuthSolver {
+    fn default() -> Self {
+        KnuthSolver::new()
+    }
+}
+
+impl KnuthSolver {
+    pub fn new() -> Self {
+        let all = Code::all();
+
+        KnuthSolver { candidates: all.clone(), all }
+    }
+
+    /// Codes that could still be the secret
+    pub fn candidates(&self) -> &[Code] {
+        &self.candidates
+    }
+
+    /// The guess that leaves the fewest candidates in the worst case. Ties go to a guess that
+    /// could itself be the secret, then to the lowest code.
+    pub fn next_guess(&self) -> Option<Code> {
+        match self.candidates.len() {
+            0 => return None,
+            // Every first guess is as good as any other, and checking them all is the slow part
+            n if n == self.all.len() => return self.candidates.first().copied(),
+            1 | 2 => return self.candidates.first().copied(),
+            _ => {}
+        }
+
+        // The worst case for `guess`, or `None` as soon as it's sure to be worse than `bound`
+        let worst_case = |guess: &Code, bound: u16| {
+            let mut counts = [0u16; (LENGTH + 1) * (LENGTH + 1)];
+            let mut worst = 0;
+
+            for candidate in &self.candidates {
+                let count = &mut counts[guess.score(candidate).index()];
+                *count += 1;
+                worst = worst.max(*count);
+
+                if worst > bound {
+                    return None;
+                }
+            }
+
+            Some(worst)
+        };
+
+        let mut best: Option<(u16, bool, Code)> = None;
+
+        for guess in &self.all {
+            let bound = best.map_or(u16::MAX, |(worst, _, _)| worst);
+
+            if let Some(worst) = worst_case(guess, bound) {
+                let option = (worst, self.candidates.binary_search(guess).is_err(), *guess);
+
+                if best.is_none_or(|best| option < best) {
+                    best = Some(option);
+                }
+            }
+        }
+
+        best.map(|(_, _, guess)| guess)
+    }
+
+    /// Drop every candidate that would have given a different answer to `guess`
+    pub fn observe(&mut self, guess: Code, score: Score) {
+        self.candidates.retain(|candidate| guess.score(candidate) == score);
+    }
+
+    /// Play a whole round against `game`, calling `on_guess` after every guess. Returns whether it
+    /// was won (it only isn't if the round has an attempt limit below 7).
+    pub fn solve(&mut self, game: &mut Mastermind, mut on_guess: impl FnMut(Code, Score, usize)) -> bool {
+        while !game.is_over() {
+            let Some(guess) = self.next_guess() else {
+                return false;
+            };
+
+            let score = game.guess(guess);
+            self.observe(guess, score);
+            on_guess(guess, score, self.candidates.len());
+        }
+
+        game.is_won()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::collections::HashMap;
+    use std::io::Cursor;
+
+    use super::*;
+
+    fn code(s: &str) -> Code {
+        s.parse().unwrap()
+    }
+
+    fn score(bulls: u8, cows: u8) -> Score {
+        Score { bulls, cows }
+    }
+
+    #[test]
+    fn scores_count_bulls_and_cows() {
+        assert_eq!(code("1234").score(&code("1234")), score(4, 0));
+        assert_eq!(code("1234").score(&code("5678")), score(0, 0));
+        assert_eq!(code("1234").score(&code("4321")), score(0, 4));
+        assert_eq!(code("1234").score(&code("1243")), score(2, 2));
+        assert_eq!(code("1234").score(&code("1567")), score(1, 0));
+        assert_eq!(code("1234").score(&code("5178")), score(0, 1));
+        assert_eq!(code("0123").score(&code("3210")), code("3210").score(&code("0123")));
+
+        assert!(score(4, 0).is_win());
+        assert_eq!(score(1, 2).to_string(), "1 bull, 2 cows");
+        assert_eq!(score(0, 1).to_string(), "0 bulls, 1 cow");
+    }
+
+    #[test]
+    fn codes_are_four_different_digits() {
+        let invalid = |input: &str, reason: &str| Err(GuessError::Invalid { input: input.to_string(), reason: reason.to_string() });
+
+        assert_eq!(" 0123\n".parse::<Code>().map(|code| code.digits()), Ok([0, 1, 2, 3]));
+        assert_eq!("".parse::<Code>(), Err(GuessError::Empty));
+        assert_eq!("1123".parse::<Code>(), invalid("1123", "has a repeated digit, every digit must be different."));
+        assert_eq!("123".parse::<Code>(), invalid("123", "isn't 4 digits long."));
+        assert_eq!("12345".parse::<Code>(), invalid("12345", "isn't 4 digits long."));
+        assert_eq!("12a4".parse::<Code>(), invalid("12a4", "isn't a code, use digits only."));
+        assert_eq!("-123".parse::<Code>(), invalid("-123", "isn't a code, use digits only."));
+
+        assert_eq!(Code::new([1, 2, 3, 10]), None);
+        assert_eq!(Code::all().len(), 5040);
+        assert_eq!(code("0987").to_string(), "0987");
+    }
+
+    #[test]
+    fn play_until_won() {
+        let mut game = Mastermind::new(code("1234"));
+        let mut output = Vec::new();
+
+        game.play(Cursor::new("1243\n12\n1234\n"), &mut output).unwrap();
+
+        assert_eq!(
+            String::from_utf8(output).unwrap(),
+            "Please input your guess.\n\
+             1243: 2 bulls, 2 cows\n\
+             Please input your guess.\n\
+             \"12\" isn't 4 digits long.\n\
+             Please input your guess.\n\
+             1234: You win! (2 attempts)\n\
+             Invalid guesses: 1\n"
+        );
+    }
+
+    #[test]
+    fn running_out_of_attempts_loses() {
+        let mut game = Mastermind::new(code("1234")).with_max_attempts(1);
+
+        game.guess(code("5678"));
+
+        assert!(game.is_over());
+        assert!(!game.is_won());
+        assert_eq!(game.guess(code("1234")), score(4, 0));
+        assert_eq!(game.attempts(), 1);
+    }
+
+    // Every game the solver can play, as a tree: the guess it makes given the answers so far, then
+    // one branch per answer. Walking it covers all 5040 secrets without replaying shared openings.
+    fn guesses_to_solve(solver: &KnuthSolver, depth: usize, found: &mut HashMap<Code, usize>) {
+        let guess = solver.next_guess().unwrap();
+        let mut branches: HashMap<Score, KnuthSolver> = HashMap::new();
+
+        for &candidate in solver.candidates() {
+            let score = guess.score(&candidate);
+
+            if score.is_win() {
+                found.insert(candidate, depth);
+                continue;
+            }
+
+            branches
+                .entry(score)
+                .or_insert_with(|| KnuthSolver { all: solver.all.clone(), candidates: Vec::new() })
+                .candidates
+                .push(candidate);
+        }
+
+        for branch in branches.values() {
+            guesses_to_solve(branch, depth + 1, found);
+        }
+    }
+
+    #[test]
+    fn knuth_solves_every_code_within_7_guesses() {
+        let mut found = HashMap::new();
+
+        guesses_to_solve(&KnuthSolver::new(), 1, &mut found);
+
+        assert_eq!(found.len(), 5040);
+        assert_eq!(found.values().max(), Some(&7));
+    }
+
+    #[test]
+    fn solve_plays_a_round() {
+        let mut game = Mastermind::new(code("9876"));
+        let mut guesses = Vec::new();
+
+        assert!(KnuthSolver::new().solve(&mut game, |guess, score, _| guesses.push((guess, score))));
+        assert_eq!(game.history(), guesses);
+        assert!(game.attempts() <= 7);
+
+        let mut game = Mastermind::new(code("9876")).with_max_attempts(1);
+        assert!(!KnuthSolver::new().solve(&mut game, |_, _, _| {}));
+    }
+}
@@ -0,0 +1,297 @@
+use std::fmt;
+use std::io::{self, BufRead, Write};
+use std::ops::RangeInclusive;
+
+use crate::Feedback;
+
+/* Reverse guessing game
+
+The player thinks of a number and the computer guesses it with a binary search. The player's
+answers use the same vocabulary as the normal game, from the point of view of the guess:
+"higher" means the guess was `Feedback::TooSmall`, "lower" means `Feedback::TooBig`.
+
+Every answer narrows the range of numbers the player could be thinking of. If an answer would
+leave nothing in that range, it contradicts an earlier answer (or the range itself), and we say which.
+
+*/
+
+/// Read a player's answer to a guess. Returns `None` for anything we don't understand.
+pub fn parse_answer(answer: &str) -> Option<Feedback> {
+    match answer.trim().to_ascii_lowercase().as_str() {
+        "h" | "higher" | "+" | ">" | "too small" => Some(Feedback::TooSmall),
+        "l" | "lower"  | "-" | "<" | "too big"   => Some(Feedback::TooBig),
+        "c" | "correct" | "=" | "yes" | "y"      => Some(Feedback::Correct),
+        _                                        => None,
+    }
+}
+
+/// One of the answers a bound came from
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Source {
+    /// An answer the player gave about a guess
+    Answer { guess: u32, feedback: Feedback },
+    /// The edge of the range the game was started with
+    RangeEdge(u32),
+}
+
+/// An answer that leaves no number the player could be thinking of
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct Contradiction {
+    pub guess: u32,
+    pub feedback: Feedback,
+    /// The earlier answer (or range edge) it can't be reconciled with
+    pub conflicts_with: Source,
+}
+
+fn describe(guess: u32, feedback: Feedback) -> String {
+    match feedback {
+        Feedback::TooSmall => format!("your number is higher than {guess}"),
+        Feedback::TooBig   => format!("your number is lower than {guess}"),
+        Feedback::Correct  => format!("your number is {guess}"),
+    }
+}
+
+impl fmt::Display for Contradiction {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        write!(f, "You said {}, ", describe(self.guess, self.feedback))?;
+
+        match self.conflicts_with {
+            Source::Answer { guess, feedback } => write!(f, "but earlier you said {}.", describe(guess, feedback)),
+            Source::RangeEdge(edge)            => write!(f, "but {edge} is the edge of the range."),
+        }
+    }
+}
+
+/// The computer's side of the reverse game
+#[derive(Debug)]
+pub struct ReverseGame {
+    low: u32,
+    high: u32,
+    low_source: Source,
+    high_source: Source,
+    history: Vec<(u32, Feedback)>,
+    found: Option<u32>,
+}
+
+impl ReverseGame {
+    pub fn new(range: RangeInclusive<u32>) -> Self {
+        let (low, high) = range.into_inner();
+
+        ReverseGame {
+            low,
+            high,
+            low_source: Source::RangeEdge(low),
+            high_source: Source::RangeEdge(high),
+            history: Vec::new(),
+            found: None,
+        }
+    }
+
+    /// Numbers the player could still be thinking of
+    pub fn remaining(&self) -> RangeInclusive<u32> {
+        self.low..=self.high
+    }
+
+    /// Every guess so far and the answer it got
+    pub fn history(&self) -> &[(u32, Feedback)] {
+        &self.history
+    }
+
+    /// The player's number, once they've said a guess was correct
+    pub fn found(&self) -> Option<u32> {
+        self.found
+    }
+
+    /// The middle of what's left, which halves the range whatever the answer
+    pub fn next_guess(&self) -> u32 {
+        self.low + (self.high - self.low) / 2
+    }
+
+    /// Apply the player's answer to `next_guess()`. An answer that contradicts earlier ones is
+    /// rejected and leaves the game as it was, so the player can correct it.
+    pub fn answer(&mut self, feedback: Feedback) -> Result<(), Contradiction> {
+        let guess = self.next_guess();
+        let source = Source::Answer { guess, feedback };
+
+        match feedback {
+            Feedback::TooSmall if guess == self.high => {
+                return Err(Contradiction { guess, feedback, conflicts_with: self.high_source });
+            }
+            Feedback::TooBig if guess == self.low => {
+                return Err(Contradiction { guess, feedback, conflicts_with: self.low_source });
+            }
+            Feedback::TooSmall => {
+                self.low = guess + 1;
+                self.low_source = source;
+            }
+            Feedback::TooBig => {
+                self.high = guess - 1;
+                self.high_source = source;
+            }
+            Feedback::Correct => self.found = Some(guess),
+        }
+
+        self.history.push((guess, feedback));
+
+        Ok(())
+    }
+
+    /// Play by writing guesses to `output` and reading the player's answers from `input`.
+    ///
+    /// Returns once the number is found, or when `input` runs out of lines.
+    pub fn play<R: BufRead, W: Write>(&mut self, mut input: R, mut output: W) -> io::Result<()> {
+
+        while self.found.is_none() {
+            let guess = self.next_guess();
+
+            writeln!(output, "Is it {guess}? (higher/lower/correct)")?;
+            output.flush()?;
+
+            let mut answer = String::new();
+
+            if input.read_line(&mut answer)? == 0 {
+                writeln!(output)?;
+                if self.low == self.high {
+                    writeln!(output, "No more input. Your number must be {}.", self.low)?;
+                } else {
+                    writeln!(output, "No more input. Your number is between {} and {}.", self.low, self.high)?;
+                }
+                return Ok(());
+            }
+
+            let Some(feedback) = parse_answer(&answer) else {
+                writeln!(output, "\"{}\" isn't an answer, say higher, lower or correct.", answer.trim())?;
+                continue;
+            };
+
+            if let Err(contradiction) = self.answer(feedback) {
+                log::debug!("contradiction: {contradiction:?}");
+                writeln!(output, "{contradiction} Let's try that one again.")?;
+            }
+        }
+
+        if let Some(number) = self.found {
+            writeln!(output, "Got it! Your number is {number} ({} guesses).", self.history.len())?;
+        }
+
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::io::Cursor;
+
+    use super::*;
+
+    fn play(game: &mut ReverseGame, input: &str) -> String {
+        let mut output = Vec::new();
+        game.play(Cursor::new(input), &mut output).unwrap();
+        String::from_utf8(output).unwrap()
+    }
+
+    #[test]
+    fn answers_narrow_the_range() {
+        let mut game = ReverseGame::new(1..=10);
+
+        assert_eq!(game.next_guess(), 5);
+        game.answer(Feedback::TooSmall).unwrap();
+        assert_eq!(game.remaining(), 6..=10);
+        assert_eq!(game.next_guess(), 8);
+        game.answer(Feedback::TooBig).unwrap();
+        assert_eq!(game.remaining(), 6..=7);
+        game.answer(Feedback::Correct).unwrap();
+
+        assert_eq!(game.found(), Some(6));
+        assert_eq!(game.history(), &[(5, Feedback::TooSmall), (8, Feedback::TooBig), (6, Feedback::Correct)]);
+    }
+
+    #[test]
+    fn contradictions_name_the_earlier_answer() {
+        let mut game = ReverseGame::new(1..=10);
+
+        game.answer(Feedback::TooSmall).unwrap(); // higher than 5
+        game.answer(Feedback::TooBig).unwrap(); // lower than 8
+
+        // Lower than 6 leaves nothing, since it's higher than 5
+        let contradiction = game.answer(Feedback::TooBig).unwrap_err();
+        assert_eq!(
+            contradiction,
+            Contradiction { guess: 6, feedback: Feedback::TooBig, conflicts_with: Source::Answer { guess: 5, feedback: Feedback::TooSmall } }
+        );
+        assert_eq!(contradiction.to_string(), "You said your number is lower than 6, but earlier you said your number is higher than 5.");
+
+        // Nothing changed, so the same guess can be answered again
+        assert_eq!(game.remaining(), 6..=7);
+        assert_eq!(game.history().len(), 2);
+        assert_eq!(game.next_guess(), 6);
+
+        game.answer(Feedback::TooSmall).unwrap();
+
+        let contradiction = game.answer(Feedback::TooSmall).unwrap_err();
+        assert_eq!(contradiction.conflicts_with, Source::Answer { guess: 8, feedback: Feedback::TooBig });
+        assert_eq!(game.remaining(), 7..=7);
+    }
+
+    #[test]
+    fn contradictions_name_the_range_edge() {
+        let mut game = ReverseGame::new(1..=3);
+
+        assert_eq!(game.answer(Feedback::TooSmall), Ok(()));
+
+        let contradiction = game.answer(Feedback::TooSmall).unwrap_err();
+        assert_eq!(contradiction.conflicts_with, Source::RangeEdge(3));
+        assert_eq!(contradiction.to_string(), "You said your number is higher than 3, but 3 is the edge of the range.");
+        assert_eq!(game.remaining(), 3..=3);
+
+        let mut game = ReverseGame::new(1..=1);
+
+        assert_eq!(game.answer(Feedback::TooBig).unwrap_err().conflicts_with, Source::RangeEdge(1));
+        assert_eq!(game.remaining(), 1..=1);
+        assert!(game.history().is_empty());
+    }
+
+    #[test]
+    fn play_until_found() {
+        let mut game = ReverseGame::new(1..=10);
+
+        assert_eq!(
+            play(&mut game, "h\nmaybe\nlower\n<\nc\n"),
+            "Is it 5? (higher/lower/correct)\n\
+             Is it 8? (higher/lower/correct)\n\
+             \"maybe\" isn't an answer, say higher, lower or correct.\n\
+             Is it 8? (higher/lower/correct)\n\
+             Is it 6? (higher/lower/correct)\n\
+             You said your number is lower than 6, but earlier you said your number is higher than 5. Let's try that one again.\n\
+             Is it 6? (higher/lower/correct)\n\
+             Got it! Your number is 6 (3 guesses).\n"
+        );
+    }
+
+    #[test]
+    fn play_until_input_runs_out() {
+        assert_eq!(
+            play(&mut ReverseGame::new(1..=10), "higher\n"),
+            "Is it 5? (higher/lower/correct)\n\
+             Is it 8? (higher/lower/correct)\n\
+             \n\
+             No more input. Your number is between 6 and 10.\n"
+        );
+        assert_eq!(
+            play(&mut ReverseGame::new(1..=3), "+\n"),
+            "Is it 2? (higher/lower/correct)\n\
+             Is it 3? (higher/lower/correct)\n\
+             \n\
+             No more input. Your number must be 3.\n"
+        );
+    }
+
+    #[test]
+    fn answers_in_any_words() {
+        for (answer, feedback) in [("H", Feedback::TooSmall), (" too big ", Feedback::TooBig), ("yes", Feedback::Correct)] {
+            assert_eq!(parse_answer(answer), Some(feedback));
+        }
+
+        assert_eq!(parse_answer("dunno"), None);
+    }
+}
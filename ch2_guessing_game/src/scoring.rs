@@ -0,0 +1,30 @@
+use std::ops::RangeInclusive;
+use std::time::Duration;
+
+/// Fewest guesses that always finds the secret with a binary search: ceil(log2(n + 1)) for a range
+/// of `n` numbers. A plain ceil(log2(n)) comes up one short whenever `n` is a power of two.
+pub fn optimal_attempts(range: &RangeInclusive<u32>) -> u32 {
+    optimal_attempts_for(u64::from(*range.end()) - u64::from(*range.start()) + 1)
+}
+
+/// `optimal_attempts` for `size` possible secrets of any kind
+pub fn optimal_attempts_for(size: u64) -> u32 {
+    u64::BITS - size.leading_zeros()
+}
+
+/// Points for winning a round that takes `optimal` attempts at best.
+///
+/// Each round is worth up to 100 points per optimal attempt, so wider ranges are worth more.
+/// That is scaled down by how many more attempts than optimal it took, and again by time taken:
+/// a round that lasts a minute is worth half as much as an instant one.
+pub fn score(optimal: u32, attempts: u32, elapsed: Duration) -> u32 {
+    if attempts == 0 {
+        return 0;
+    }
+
+    let optimal = f64::from(optimal);
+    let efficiency = (optimal / f64::from(attempts)).min(1.0);
+    let speed = 1.0 / (1.0 + elapsed.as_secs_f64() / 60.0);
+
+    (100.0 * optimal * efficiency * speed).round() as u32
+}
@@ -0,0 +1,50 @@
+use std::ops::RangeInclusive;
+
+use rand::rngs::StdRng;
+use rand::{Rng, SeedableRng};
+
+/// Anything that can pick the secret number for a round
+pub trait SecretSource {
+    fn secret(&mut self, range: RangeInclusive<u32>) -> u32;
+}
+
+/// Picks secrets from a `StdRng`, so the same seed always replays the same sequence of secrets
+#[derive(Debug)]
+pub struct SeededSource {
+    seed: u64,
+    rng: StdRng,
+}
+
+impl SeededSource {
+    pub fn new(seed: u64) -> Self {
+        SeededSource {
+            seed,
+            rng: StdRng::seed_from_u64(seed),
+        }
+    }
+
+    /// Seed from the thread RNG. The seed is still kept around so the session can be replayed.
+    pub fn random() -> Self {
+        SeededSource::new(rand::thread_rng().gen())
+    }
+
+    pub fn seed(&self) -> u64 {
+        self.seed
+    }
+}
+
+impl SecretSource for SeededSource {
+    fn secret(&mut self, range: RangeInclusive<u32>) -> u32 {
+        self.rng.gen_range(range)
+    }
+}
+
+/// Always hands out the same secret, whatever the range. Handy for tests and demos.
+#[derive(Debug, Clone, Copy)]
+pub struct FixedSource(pub u32);
+
+impl SecretSource for FixedSource {
+    fn secret(&mut self, _range: RangeInclusive<u32>) -> u32 {
+        self.0
+    }
+}
@@ -0,0 +1,352 @@
+use std::collections::HashMap;
+use std::io::{self, BufRead, BufReader, Write};
+use std::net::{Shutdown, TcpListener, TcpStream};
+use std::ops::RangeInclusive;
+use std::sync::mpsc::{self, Receiver, Sender};
+use std::sync::{Arc, Mutex};
+use std::thread;
+use std::time::Duration;
+
+use crate::{parse_guess, Feedback, GuessingGame, SecretSource};
+
+/* Multiplayer server
+
+Everyone connected guesses at the same secret number over a line-based TCP protocol, so any
+line-oriented client (netcat, telnet, ...) can play. Client to server:
+
+    NAME <name>     pick the name shown when you win (defaults to player<id>)
+    GUESS <n>       guess a number
+    QUIT            leave
+
+Server to client:
+
+    HELLO <id> <round> <min> <max>    on connect
+    LOW | HIGH | WIN                  your guess was too small, too big, or right
+    WINNER <name> <secret> <attempts> sent to everyone when a round is won...
+    ROUND <round> <min> <max>         ...followed by a new round with a new secret
+    OK | BYE | ERR <message>
+
+*/
+
+/// A parsed line from a client
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum Request {
+    Name(String),
+    Guess(u32),
+    Quit,
+}
+
+impl Request {
+    pub fn parse(line: &str) -> Result<Request, String> {
+        let line = line.trim();
+        let (command, argument) = line.split_once(' ').unwrap_or((line, ""));
+        let argument = argument.trim();
+
+        match command.to_ascii_uppercase().as_str() {
+            "NAME" if argument.is_empty() => Err(String::from("NAME needs a name")),
+            "NAME"                        => Ok(Request::Name(argument.to_string())),
+            "GUESS"                       => parse_guess(argument).map(Request::Guess).map_err(|err| err.to_string()),
+            "QUIT"                        => Ok(Request::Quit),
+            ""                            => Err(String::from("empty command")),
+            other                         => Err(format!("unknown command {other}")),
+        }
+    }
+}
+
+/// The protocol word for a guess's feedback
+pub fn response(feedback: Feedback) -> &'static str {
+    match feedback {
+        Feedback::TooSmall => "LOW",
+        Feedback::TooBig   => "HIGH",
+        Feedback::Correct  => "WIN",
+    }
+}
+
+/// How long a write to a client can stall before the client is dropped
+const WRITE_TIMEOUT: Duration = Duration::from_secs(5);
+
+struct Player {
+    name: String,
+    attempts: u32,
+    // Lines queued for the player's writer thread, so nothing is written while the room is locked
+    outbox: Sender<String>,
+}
+
+impl Player {
+    // False once the player's connection is gone
+    fn send(&self, line: String) -> bool {
+        self.outbox.send(line).is_ok()
+    }
+}
+
+// Write queued lines to a client until the queue closes or the client stops reading
+fn write_lines(mut stream: TcpStream, queue: Receiver<String>) {
+    for line in queue {
+        if let Err(err) = writeln!(stream, "{line}") {
+            log::info!("failed to write to {:?}: {err}", stream.peer_addr());
+            // Also ends the connection's reading side, which removes the player
+            let _ = stream.shutdown(Shutdown::Both);
+            return;
+        }
+    }
+}
+
+// Everything the connection threads share
+struct Room {
+    range: RangeInclusive<u32>,
+    source: Box<dyn SecretSource + Send>,
+    game: GuessingGame,
+    round: u32,
+    players: HashMap<u32, Player>,
+    next_id: u32,
+}
+
+impl Room {
+    fn new_game(range: &RangeInclusive<u32>, source: &mut dyn SecretSource) -> GuessingGame {
+        let secret_number = source.secret(range.clone());
+        log::debug!("the secret number is {secret_number}");
+
+        GuessingGame::new(secret_number, range.clone())
+    }
+
+    // Send a line to every player, dropping anyone who can't be reached
+    fn broadcast(&mut self, line: &str) {
+        self.players.retain(|id, player| {
+            let sent = player.send(line.to_string());
+            if !sent {
+                log::info!("dropping player {id}");
+            }
+            sent
+        });
+    }
+
+    fn next_round(&mut self) {
+        self.round += 1;
+        self.game = Room::new_game(&self.range, self.source.as_mut());
+
+        for player in self.players.values_mut() {
+            player.attempts = 0;
+        }
+
+        let line = format!("ROUND {} {} {}", self.round, self.range.start(), self.range.end());
+        self.broadcast(&line);
+    }
+
+    // Handle one request, returning the reply for the player who sent it
+    fn handle(&mut self, id: u32, request: Request) -> String {
+        match request {
+            Request::Name(name) => {
+                if let Some(player) = self.players.get_mut(&id) {
+                    player.name = name.replace(char::is_whitespace, "_");
+                }
+                String::from("OK")
+            }
+            Request::Quit => String::from("BYE"),
+            Request::Guess(guess) => {
+                let feedback = match self.game.guess(guess) {
+                    Ok(feedback) => feedback,
+                    Err(err)     => return format!("ERR {err}"),
+                };
+
+                let Some(player) = self.players.get_mut(&id) else {
+                    return String::from("ERR not connected");
+                };
+                player.attempts += 1;
+
+                if feedback == Feedback::Correct {
+                    let winner = format!("WINNER {} {} {}", player.name, guess, player.attempts);
+                    log::info!("round {} won by {}", self.round, player.name);
+
+                    // Reply first so the winner hears WIN before the broadcast
+                    player.send(String::from("WIN"));
+                    self.broadcast(&winner);
+                    self.next_round();
+
+                    return String::new();
+                }
+
+                response(feedback).to_string()
+            }
+        }
+    }
+}
+
+/// A guessing game shared by everyone connected to it
+#[derive(Clone)]
+pub struct Server {
+    room: Arc<Mutex<Room>>,
+}
+
+impl Server {
+    pub fn new(range: RangeInclusive<u32>, mut source: impl SecretSource + Send + 'static) -> Self {
+        let game = Room::new_game(&range, &mut source);
+
+        let room = Room {
+            range,
+            source: Box::new(source),
+            game,
+            round: 1,
+            players: HashMap::new(),
+            next_id: 1,
+        };
+
+        Server { room: Arc::new(Mutex::new(room)) }
+    }
+
+    /// Accept players from `listener` forever, one thread each
+    pub fn serve(&self, listener: TcpListener) -> io::Result<()> {
+        for stream in listener.incoming() {
+            let stream = match stream {
+                Ok(stream) => stream,
+                Err(err) => {
+                    log::warn!("failed to accept a connection: {err}");
+                    continue;
+                }
+            };
+
+            let server = self.clone();
+
+            thread::spawn(move || {
+                if let Err(err) = server.handle_connection(stream) {
+                    log::info!("connection closed: {err}");
+                }
+            });
+        }
+
+        Ok(())
+    }
+
+    fn handle_connection(&self, stream: TcpStream) -> io::Result<()> {
+        let peer = stream.peer_addr()?;
+        let reader = BufReader::new(stream.try_clone()?);
+
+        stream.set_write_timeout(Some(WRITE_TIMEOUT))?;
+
+        let (outbox, queue) = mpsc::channel();
+        let writer = thread::spawn(move || write_lines(stream, queue));
+
+        let id = {
+            let mut room = self.room.lock().expect("room lock poisoned");
+            let id = room.next_id;
+            room.next_id += 1;
+
+            let _ = outbox.send(format!("HELLO {id} {} {} {}", room.round, room.range.start(), room.range.end()));
+
+            room.players.insert(id, Player { name: format!("player{id}"), attempts: 0, outbox: outbox.clone() });
+            id
+        };
+
+        log::info!("player {id} connected from {peer}");
+
+        for line in reader.lines() {
+            let line = line?;
+
+            let (reply, quit) = match Request::parse(&line) {
+                Ok(request) => {
+                    let quit = request == Request::Quit;
+                    (self.room.lock().expect("room lock poisoned").handle(id, request), quit)
+                }
+                Err(err) => (format!("ERR {err}"), false),
+            };
+
+            // An empty reply means it was already sent
+            if !reply.is_empty() && outbox.send(reply).is_err() {
+                break;
+            }
+
+            if quit {
+                break;
+            }
+        }
+
+        self.room.lock().expect("room lock poisoned").players.remove(&id);
+        log::info!("player {id} left");
+
+        // Closing the queue lets the writer finish sending what's left (like BYE) and stop
+        drop(outbox);
+        let _ = writer.join();
+
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::FixedSource;
+
+    // A connected client that reads whole lines, failing instead of hanging if none come
+    struct Client {
+        reader: BufReader<TcpStream>,
+        writer: TcpStream,
+    }
+
+    impl Client {
+        fn connect(server: &Server) -> Client {
+            let listener = TcpListener::bind("127.0.0.1:0").unwrap();
+            let address = listener.local_addr().unwrap();
+            let server = server.clone();
+
+            // One listener per client keeps the test from depending on accept order
+            thread::spawn(move || {
+                let (stream, _) = listener.accept().unwrap();
+                let _ = server.handle_connection(stream);
+            });
+
+            let stream = TcpStream::connect(address).unwrap();
+            stream.set_read_timeout(Some(Duration::from_secs(5))).unwrap();
+
+            Client { reader: BufReader::new(stream.try_clone().unwrap()), writer: stream }
+        }
+
+        fn send(&mut self, line: &str) {
+            writeln!(self.writer, "{line}").unwrap();
+        }
+
+        fn receive(&mut self) -> String {
+            let mut line = String::new();
+            self.reader.read_line(&mut line).unwrap();
+            line.trim_end().to_string()
+        }
+    }
+
+    #[test]
+    fn requests_parse() {
+        assert_eq!(Request::parse("guess 42"), Ok(Request::Guess(42)));
+        assert_eq!(Request::parse("NAME  ada "), Ok(Request::Name(String::from("ada"))));
+        assert_eq!(Request::parse("QUIT"), Ok(Request::Quit));
+        assert!(Request::parse("NAME").is_err());
+        assert!(Request::parse("DANCE").is_err());
+    }
+
+    #[test]
+    fn a_win_is_broadcast_to_everyone() {
+        let server = Server::new(1..=100, FixedSource(42));
+
+        // Ids are handed out as connections are handled, so bob only connects once alice has hers
+        let mut alice = Client::connect(&server);
+        assert_eq!(alice.receive(), "HELLO 1 1 1 100");
+
+        let mut bob = Client::connect(&server);
+        assert_eq!(bob.receive(), "HELLO 2 1 1 100");
+
+        alice.send("NAME alice");
+        assert_eq!(alice.receive(), "OK");
+
+        bob.send("GUESS 10");
+        assert_eq!(bob.receive(), "LOW");
+
+        alice.send("GUESS 50");
+        assert_eq!(alice.receive(), "HIGH");
+        alice.send("GUESS 42");
+        assert_eq!(alice.receive(), "WIN");
+
+        for client in [&mut alice, &mut bob] {
+            assert_eq!(client.receive(), "WINNER alice 42 2");
+            assert_eq!(client.receive(), "ROUND 2 1 100");
+        }
+
+        bob.send("QUIT");
+        assert_eq!(bob.receive(), "BYE");
+    }
+}
@@ -0,0 +1,557 @@
+use std::ops::RangeInclusive;
+
+use rand::rngs::StdRng;
+use rand::{Rng, SeedableRng};
+
+use crate::{Feedback, GuessingGame};
+
+/* Solvers
+
+Strategies for playing the guessing game automatically. Each one picks a guess, is told how it
+went, and picks again until it hits the secret. `solve` plays a strategy against a `GuessingGame`,
+optionally through a `LyingOracle` that sometimes swaps "too small" and "too big" around.
+
+*/
+
+/// A way of picking guesses
+pub trait Strategy {
+    fn name(&self) -> &'static str;
+
+    /// Forget everything and get ready to play over `range`
+    fn reset(&mut self, range: RangeInclusive<u32>);
+
+    fn next_guess(&mut self) -> u32;
+
+    /// Learn from the feedback a guess got
+    fn observe(&mut self, guess: u32, feedback: Feedback);
+}
+
+// The lowest and highest numbers the secret could still be, as far as a strategy trusts its feedback
+#[derive(Debug, Clone)]
+struct Bounds {
+    range: RangeInclusive<u32>,
+    low: u32,
+    high: u32,
+}
+
+impl Bounds {
+    fn new(range: RangeInclusive<u32>) -> Self {
+        Bounds {
+            low: *range.start(),
+            high: *range.end(),
+            range,
+        }
+    }
+
+    // Feedback that rules out everything left can only be a lie, so start over from the full range
+    fn narrow(&mut self, guess: u32, feedback: Feedback) {
+        match feedback {
+            Feedback::TooSmall if guess >= self.high => *self = Bounds::new(self.range.clone()),
+            Feedback::TooBig if guess <= self.low    => *self = Bounds::new(self.range.clone()),
+            Feedback::TooSmall                       => self.low = self.low.max(guess + 1),
+            Feedback::TooBig                         => self.high = self.high.min(guess - 1),
+            Feedback::Correct                        => {}
+        }
+    }
+}
+
+/// Always guess the middle of what's left
+#[derive(Debug)]
+pub struct BinarySearch {
+    bounds: Bounds,
+}
+
+impl BinarySearch {
+    pub fn new() -> Self {
+        BinarySearch { bounds: Bounds::new(0..=0) }
+    }
+}
+
+impl Default for BinarySearch {
+    fn default() -> Self {
+        BinarySearch::new()
+    }
+}
+
+impl Strategy for BinarySearch {
+    fn name(&self) -> &'static str {
+        "binary"
+    }
+
+    fn reset(&mut self, range: RangeInclusive<u32>) {
+        self.bounds = Bounds::new(range);
+    }
+
+    fn next_guess(&mut self) -> u32 {
+        self.bounds.low + (self.bounds.high - self.bounds.low) / 2
+    }
+
+    fn observe(&mut self, guess: u32, feedback: Feedback) {
+        self.bounds.narrow(guess, feedback);
+    }
+}
+
+/// Guess anywhere in what's left, at random
+#[derive(Debug)]
+pub struct RandomGuess {
+    bounds: Bounds,
+    rng: StdRng,
+}
+
+impl RandomGuess {
+    pub fn new(seed: u64) -> Self {
+        RandomGuess {
+            bounds: Bounds::new(0..=0),
+            rng: StdRng::seed_from_u64(seed),
+        }
+    }
+}
+
+impl Strategy for RandomGuess {
+    fn name(&self) -> &'static str {
+        "random"
+    }
+
+    fn reset(&mut self, range: RangeInclusive<u32>) {
+        self.bounds = Bounds::new(range);
+    }
+
+    fn next_guess(&mut self) -> u32 {
+        self.rng.gen_range(self.bounds.low..=self.bounds.high)
+    }
+
+    fn observe(&mut self, guess: u32, feedback: Feedback) {
+        self.bounds.narrow(guess, feedback);
+    }
+}
+
+/// Split what's left at the golden ratio instead of down the middle.
+/// Lopsided on purpose: it's here to show how much worse than binary search that is.
+#[derive(Debug)]
+pub struct GoldenSection {
+    bounds: Bounds,
+}
+
+impl GoldenSection {
+    // 1 - 1/phi
+    const RATIO: f64 = 0.381_966_011_250_105_1;
+
+    pub fn new() -> Self {
+        GoldenSection { bounds: Bounds::new(0..=0) }
+    }
+}
+
+impl Default for GoldenSection {
+    fn default() -> Self {
+        GoldenSection::new()
+    }
+}
+
+impl Strategy for GoldenSection {
+    fn name(&self) -> &'static str {
+        "golden"
+    }
+
+    fn reset(&mut self, range: RangeInclusive<u32>) {
+        self.bounds = Bounds::new(range);
+    }
+
+    fn next_guess(&mut self) -> u32 {
+        let width = f64::from(self.bounds.high - self.bounds.low);
+
+        self.bounds.low + (width * Self::RATIO).round() as u32
+    }
+
+    fn observe(&mut self, guess: u32, feedback: Feedback) {
+        self.bounds.narrow(guess, feedback);
+    }
+}
+
+// A run of numbers that all disagree with the same number of answers so far
+#[derive(Debug, Clone, Copy)]
+struct Segment {
+    low: u32,
+    high: u32,
+    lies: u32,
+}
+
+/// Binary search that survives being lied to.
+///
+/// Instead of throwing numbers away, it counts how many answers each number disagrees with (how
+/// many lies the oracle would have had to tell if that number were the secret). Numbers needing
+/// more than `max_lies` lies are out, and guesses split the rest by weight, where fewer lies means
+/// more weight. If the oracle lies more than expected the tolerance goes up rather than giving up.
+#[derive(Debug)]
+pub struct LieTolerant {
+    max_lies: u32,
+    tolerance: u32,
+    segments: Vec<Segment>,
+}
+
+impl LieTolerant {
+    pub fn new(max_lies: u32) -> Self {
+        LieTolerant {
+            max_lies,
+            tolerance: max_lies,
+            segments: Vec::new(),
+        }
+    }
+
+    // Make sure a segment boundary falls right after `at`
+    fn split_after(&mut self, at: u32) {
+        if let Some(index) = self.segments.iter().position(|s| s.low <= at && at < s.high) {
+            let segment = self.segments[index];
+
+            self.segments[index].high = at;
+            self.segments.insert(index + 1, Segment { low: at + 1, ..segment });
+        }
+    }
+
+    fn merge(&mut self) {
+        self.segments.dedup_by(|next, prev| {
+            let same = next.lies == prev.lies;
+            if same {
+                prev.high = next.high;
+            }
+            same
+        });
+    }
+
+    fn weight(&self, segment: &Segment) -> u64 {
+        u64::from(self.tolerance + 1 - segment.lies)
+    }
+}
+
+impl Strategy for LieTolerant {
+    fn name(&self) -> &'static str {
+        "tolerant"
+    }
+
+    fn reset(&mut self, range: RangeInclusive<u32>) {
+        let (low, high) = range.into_inner();
+
+        self.tolerance = self.max_lies;
+        self.segments = vec![Segment { low, high, lies: 0 }];
+    }
+
+    fn next_guess(&mut self) -> u32 {
+        // Everything ruled out means more lies than we allowed for
+        while self.segments.iter().all(|s| s.lies > self.tolerance) {
+            self.tolerance += 1;
+        }
+
+        let candidates: Vec<Segment> = self.segments.iter()
+            .filter(|s| s.lies <= self.tolerance)
+            .copied()
+            .collect();
+
+        let total: u64 = candidates.iter()
+            .map(|s| (u64::from(s.high) - u64::from(s.low) + 1) * self.weight(s))
+            .sum();
+
+        // Weighted median: the first number where the running weight reaches half the total
+        let mut remaining = total / 2;
+
+        for segment in &candidates {
+            let weight = self.weight(segment);
+            let segment_total = (u64::from(segment.high) - u64::from(segment.low) + 1) * weight;
+
+            if remaining < segment_total {
+                return segment.low + (remaining / weight) as u32;
+            }
+
+            remaining -= segment_total;
+        }
+
+        candidates.last().map_or(0, |s| s.high)
+    }
+
+    fn observe(&mut self, guess: u32, feedback: Feedback) {
+        // Which numbers does this answer say can't be the secret?
+        let ruled_out: Box<dyn Fn(&Segment) -> bool> = match feedback {
+            Feedback::TooSmall => {
+                self.split_after(guess);
+                Box::new(move |s| s.high <= guess)
+            }
+            Feedback::TooBig => {
+                if guess > 0 {
+                    self.split_after(guess - 1);
+                }
+                Box::new(move |s| s.low >= guess)
+            }
+            Feedback::Correct => return,
+        };
+
+        for segment in self.segments.iter_mut().filter(|s| ruled_out(s)) {
+            segment.lies += 1;
+        }
+
+        self.merge();
+    }
+}
+
+/// Sits between a game and a strategy, swapping "too small" and "too big" some of the time
+#[derive(Debug)]
+pub struct LyingOracle {
+    lie_rate: f64,
+    rng: StdRng,
+}
+
+impl LyingOracle {
+    /// Lie with probability `lie_rate` (clamped to 0..=1). A correct guess is never lied about.
+    pub fn new(lie_rate: f64, seed: u64) -> Self {
+        LyingOracle {
+            lie_rate: lie_rate.clamp(0.0, 1.0),
+            rng: StdRng::seed_from_u64(seed),
+        }
+    }
+
+    pub fn relay(&mut self, feedback: Feedback) -> Feedback {
+        if feedback == Feedback::Correct || !self.rng.gen_bool(self.lie_rate) {
+            return feedback;
+        }
+
+        match feedback {
+            Feedback::TooSmall => Feedback::TooBig,
+            _                  => Feedback::TooSmall,
+        }
+    }
+}
+
+/// Every strategy by name, in the order they're listed in
+pub const STRATEGIES: [&str; 4] = ["binary", "random", "golden", "tolerant"];
+
+/// Build a strategy from its name. `seed` drives any randomness it uses, and `max_lies` is how
+/// many lies per game the tolerant search allows for up front.
+pub fn strategy_by_name(name: &str, seed: u64, max_lies: u32) -> Option<Box<dyn Strategy>> {
+    match name {
+        "binary"   => Some(Box::new(BinarySearch::new())),
+        "random"   => Some(Box::new(RandomGuess::new(seed))),
+        "golden"   => Some(Box::new(GoldenSection::new())),
+        "tolerant" => Some(Box::new(LieTolerant::new(max_lies))),
+        _          => None,
+    }
+}
+
+/// Let `strategy` play `game` until it wins or has made `give_up_after` attempts.
+/// Feedback goes through `oracle` first, if there is one.
+pub fn solve(
+    game: &mut GuessingGame,
+    strategy: &mut dyn Strategy,
+    mut oracle: Option<&mut LyingOracle>,
+    give_up_after: u32,
+) -> bool {
+    strategy.reset(game.range().clone());
+
+    while game.attempts() < give_up_after {
+        let guess = strategy.next_guess();
+
+        let feedback = game.guess(guess)
+            .expect("strategies only guess inside the range");
+
+        if feedback == Feedback::Correct {
+            return true;
+        }
+
+        let feedback = match oracle.as_deref_mut() {
+            Some(oracle) => oracle.relay(feedback),
+            None         => feedback,
+        };
+
+        strategy.observe(guess, feedback);
+    }
+
+    false
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    // Every strategy, ready to play over `range`
+    fn strategies(max_lies: u32) -> Vec<Box<dyn Strategy>> {
+        STRATEGIES.iter().map(|name| strategy_by_name(name, 7, max_lies).unwrap()).collect()
+    }
+
+    fn play(strategy: &mut dyn Strategy, secret: u32, range: RangeInclusive<u32>, give_up_after: u32) -> Option<u32> {
+        let mut game = GuessingGame::new(secret, range);
+
+        solve(&mut game, strategy, None, give_up_after).then(|| game.attempts())
+    }
+
+    #[test]
+    fn bounds_narrow_to_what_the_feedback_allows() {
+        let mut bounds = Bounds::new(1..=100);
+
+        bounds.narrow(40, Feedback::TooSmall);
+        assert_eq!((bounds.low, bounds.high), (41, 100));
+
+        bounds.narrow(60, Feedback::TooBig);
+        assert_eq!((bounds.low, bounds.high), (41, 59));
+
+        // Old news doesn't widen anything
+        bounds.narrow(30, Feedback::TooSmall);
+        bounds.narrow(70, Feedback::TooBig);
+        assert_eq!((bounds.low, bounds.high), (41, 59));
+
+        bounds.narrow(50, Feedback::Correct);
+        assert_eq!((bounds.low, bounds.high), (41, 59));
+    }
+
+    #[test]
+    fn bounds_start_over_when_feedback_rules_everything_out() {
+        let mut bounds = Bounds::new(1..=100);
+        bounds.narrow(40, Feedback::TooSmall);
+
+        bounds.narrow(40, Feedback::TooBig);
+        assert_eq!((bounds.low, bounds.high), (1, 100));
+
+        bounds.narrow(60, Feedback::TooBig);
+        bounds.narrow(60, Feedback::TooSmall);
+        assert_eq!((bounds.low, bounds.high), (1, 100));
+
+        // The edges of the widest range can't be stepped past
+        let mut bounds = Bounds::new(0..=u32::MAX);
+        bounds.narrow(0, Feedback::TooBig);
+        bounds.narrow(u32::MAX, Feedback::TooSmall);
+        assert_eq!((bounds.low, bounds.high), (0, u32::MAX));
+    }
+
+    #[test]
+    fn binary_search_guesses_the_middle() {
+        let mut binary = BinarySearch::new();
+        binary.reset(1..=100);
+
+        assert_eq!(binary.next_guess(), 50);
+        binary.observe(50, Feedback::TooSmall);
+        assert_eq!(binary.next_guess(), 75);
+        binary.observe(75, Feedback::TooBig);
+        assert_eq!(binary.next_guess(), 62);
+    }
+
+    #[test]
+    fn golden_section_guesses_off_centre() {
+        let mut golden = GoldenSection::new();
+        golden.reset(1..=100);
+
+        assert_eq!(golden.next_guess(), 39);
+        golden.observe(39, Feedback::TooSmall);
+        assert_eq!(golden.next_guess(), 63);
+    }
+
+    #[test]
+    fn random_guesses_stay_in_bounds_and_follow_the_seed() {
+        let guesses = |seed| {
+            let mut random = RandomGuess::new(seed);
+            random.reset(1..=100);
+            random.observe(30, Feedback::TooSmall);
+            random.observe(60, Feedback::TooBig);
+
+            (0..20).map(|_| random.next_guess()).collect::<Vec<_>>()
+        };
+
+        assert!(guesses(1).iter().all(|guess| (31..=59).contains(guess)));
+        assert_eq!(guesses(1), guesses(1));
+        assert_ne!(guesses(1), guesses(2));
+    }
+
+    #[test]
+    fn every_strategy_finds_every_secret() {
+        for mut strategy in strategies(1) {
+            for secret in 1..=100 {
+                assert!(play(strategy.as_mut(), secret, 1..=100, 100).is_some(), "{} didn't find {secret}", strategy.name());
+            }
+        }
+    }
+
+    #[test]
+    fn binary_and_honest_tolerant_searches_stay_within_the_optimal_attempts() {
+        for name in ["binary", "tolerant"] {
+            let mut strategy = strategy_by_name(name, 0, 0).unwrap();
+
+            for secret in 1..=100 {
+                assert!(play(strategy.as_mut(), secret, 1..=100, 7).is_some(), "{name} needed more than 7 for {secret}");
+            }
+        }
+    }
+
+    #[test]
+    fn strategies_handle_the_widest_range() {
+        for mut strategy in strategies(1) {
+            for secret in [0, 1, u32::MAX / 2, u32::MAX - 1, u32::MAX] {
+                let mut game = GuessingGame::new(secret, 0..=u32::MAX);
+                let mut oracle = LyingOracle::new(0.1, 3);
+
+                // Random guessing is slow here, it only has to not panic
+                solve(&mut game, strategy.as_mut(), Some(&mut oracle), 200);
+            }
+
+            if strategy.name() != "random" {
+                assert!(play(strategy.as_mut(), u32::MAX, 0..=u32::MAX, 100).is_some(), "{}", strategy.name());
+            }
+        }
+    }
+
+    #[test]
+    fn tolerant_search_survives_a_lie() {
+        let mut tolerant = LieTolerant::new(1);
+        tolerant.reset(1..=100);
+
+        // The secret is 80, but the first answer lies
+        tolerant.observe(50, Feedback::TooBig);
+
+        let mut game = GuessingGame::new(80, 1..=100);
+        let mut attempts = 1;
+
+        loop {
+            let guess = tolerant.next_guess();
+            let feedback = game.guess(guess).unwrap();
+            attempts += 1;
+
+            if feedback == Feedback::Correct {
+                break;
+            }
+
+            tolerant.observe(guess, feedback);
+            assert!(attempts < 20, "lost after the lie");
+        }
+    }
+
+    #[test]
+    fn lying_oracle() {
+        let relay = |rate: f64| {
+            let mut oracle = LyingOracle::new(rate, 9);
+            [Feedback::TooSmall, Feedback::TooBig, Feedback::Correct].map(|feedback| oracle.relay(feedback))
+        };
+
+        assert_eq!(relay(0.0), [Feedback::TooSmall, Feedback::TooBig, Feedback::Correct]);
+        assert_eq!(relay(1.0), [Feedback::TooBig, Feedback::TooSmall, Feedback::Correct]);
+
+        // Rates are clamped to 0..=1
+        assert_eq!(relay(-3.0), relay(0.0));
+        assert_eq!(relay(5.0), relay(1.0));
+
+        // Roughly as often as asked
+        let mut oracle = LyingOracle::new(0.25, 9);
+        let lies = (0..10_000).filter(|_| oracle.relay(Feedback::TooSmall) == Feedback::TooBig).count();
+        assert!((2_000..3_000).contains(&lies), "{lies} lies");
+    }
+
+    #[test]
+    fn strategies_by_name() {
+        for name in STRATEGIES {
+            assert_eq!(strategy_by_name(name, 0, 0).map(|strategy| strategy.name()), Some(name));
+        }
+
+        assert!(strategy_by_name("psychic", 0, 0).is_none());
+    }
+
+    #[test]
+    fn solve_gives_up() {
+        let mut game = GuessingGame::new(100, 1..=100);
+        let mut golden = GoldenSection::new();
+
+        assert!(!solve(&mut game, &mut golden, None, 2));
+        assert_eq!(game.attempts(), 2);
+    }
+}
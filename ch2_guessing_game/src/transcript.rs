@@ -0,0 +1,409 @@
+use std::cell::RefCell;
+use std::collections::VecDeque;
+use std::fmt;
+use std::fs::File;
+use std::io::{self, BufRead, BufReader, BufWriter, Read, Write};
+use std::path::Path;
+use std::rc::Rc;
+use std::time::{Duration, Instant, SystemTime, UNIX_EPOCH};
+
+use serde::{Deserialize, Serialize};
+
+use crate::{Difficulty, GuessingGame, ManualClock, SecretSource, SeededSource};
+
+/* Transcripts
+
+A transcript records everything said in a session, as JSON Lines: a `start` event with the
+settings needed to set up the same round again, then every line the game wrote (`output`) and
+every line the player typed (`input`), each with milliseconds since the start.
+
+Replaying feeds the recorded input to a fresh game set up from the `start` event and checks the
+game says exactly what it said the first time. The game's clock only moves when a line of input
+arrives, in both recording and replay, so even scores (which depend on time) come out the same.
+
+*/
+
+/// One line of a transcript
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+#[serde(tag = "kind", rename_all = "snake_case")]
+pub enum Event {
+    Start {
+        /// Seconds since the Unix epoch, when the session started
+        timestamp: u64,
+        settings: Settings,
+    },
+    Output { t_ms: u64, text: String },
+    Input { t_ms: u64, text: String },
+}
+
+/// Everything that decides how a round plays out
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+pub struct Settings {
+    pub seed: u64,
+    pub difficulty: Difficulty,
+    pub max_attempts: Option<u32>,
+    pub hints: bool,
+}
+
+impl Settings {
+    /// The round these settings describe
+    pub fn new_game(&self) -> GuessingGame {
+        let range = self.difficulty.range();
+        let secret_number = SeededSource::new(self.seed).secret(range.clone());
+
+        let mut game = GuessingGame::new(secret_number, range);
+
+        if let Some(max_attempts) = self.max_attempts {
+            game = game.with_max_attempts(max_attempts);
+        }
+
+        if self.hints {
+            game = game.with_hints();
+        }
+
+        game
+    }
+
+    /// The line the game opens with
+    pub fn greeting(&self) -> String {
+        let range = self.difficulty.range();
+
+        format!("Guess the number between {} and {}! ({})", range.start(), range.end(), self.difficulty)
+    }
+}
+
+#[derive(Debug)]
+struct Log {
+    started: Instant,
+    clock: ManualClock,
+    events: Vec<Event>,
+    partial_input: Vec<u8>,
+    partial_output: Vec<u8>,
+}
+
+impl Log {
+    fn millis(&self) -> u64 {
+        u64::try_from(self.started.elapsed().as_millis()).unwrap_or(u64::MAX)
+    }
+
+    // A line of input has arrived, so this is when the game sees it happen
+    fn input_line(&mut self) {
+        let t_ms = self.millis();
+        let text = String::from_utf8_lossy(&self.partial_input).trim_end_matches(['\n', '\r']).to_string();
+
+        self.partial_input.clear();
+        self.clock.set(Duration::from_millis(t_ms));
+        self.events.push(Event::Input { t_ms, text });
+    }
+
+    fn output_line(&mut self) {
+        let t_ms = self.millis();
+        let text = String::from_utf8_lossy(&self.partial_output).trim_end_matches(['\n', '\r']).to_string();
+
+        self.partial_output.clear();
+        self.events.push(Event::Output { t_ms, text });
+    }
+}
+
+/// Records a session. Hand `input()` and `output()` to the game in place of the real ones, and
+/// `clock()` to the game itself.
+#[derive(Debug, Clone)]
+pub struct Recorder {
+    log: Rc<RefCell<Log>>,
+}
+
+impl Recorder {
+    pub fn new(settings: Settings) -> Self {
+        let timestamp = SystemTime::now()
+            .duration_since(UNIX_EPOCH)
+            .map(|since| since.as_secs())
+            .unwrap_or(0);
+
+        let log = Log {
+            started: Instant::now(),
+            clock: ManualClock::new(),
+            events: vec![Event::Start { timestamp, settings }],
+            partial_input: Vec::new(),
+            partial_output: Vec::new(),
+        };
+
+        Recorder { log: Rc::new(RefCell::new(log)) }
+    }
+
+    pub fn clock(&self) -> ManualClock {
+        self.log.borrow().clock.clone()
+    }
+
+    pub fn input<R: BufRead>(&self, inner: R) -> RecordingInput<R> {
+        RecordingInput { inner, log: Rc::clone(&self.log) }
+    }
+
+    pub fn output<W: Write>(&self, inner: W) -> RecordingOutput<W> {
+        RecordingOutput { inner, log: Rc::clone(&self.log) }
+    }
+
+    /// Everything recorded so far, including any unfinished lines
+    pub fn events(&self) -> Vec<Event> {
+        let mut log = self.log.borrow_mut();
+
+        if !log.partial_input.is_empty() {
+            log.input_line();
+        }
+        if !log.partial_output.is_empty() {
+            log.output_line();
+        }
+
+        log.events.clone()
+    }
+
+    pub fn save(&self, path: impl AsRef<Path>) -> io::Result<()> {
+        write_events(BufWriter::new(File::create(path)?), &self.events())
+    }
+}
+
+/// Input that's recorded as it's read
+#[derive(Debug)]
+pub struct RecordingInput<R> {
+    inner: R,
+    log: Rc<RefCell<Log>>,
+}
+
+impl<R: BufRead> Read for RecordingInput<R> {
+    fn read(&mut self, buf: &mut [u8]) -> io::Result<usize> {
+        let available = self.fill_buf()?;
+        let amount = available.len().min(buf.len());
+
+        buf[..amount].copy_from_slice(&available[..amount]);
+        self.consume(amount);
+
+        Ok(amount)
+    }
+}
+
+impl<R: BufRead> BufRead for RecordingInput<R> {
+    fn fill_buf(&mut self) -> io::Result<&[u8]> {
+        let available = self.inner.fill_buf()?;
+
+        // Running dry finishes off whatever the last line was
+        if available.is_empty() {
+            let mut log = self.log.borrow_mut();
+            if !log.partial_input.is_empty() {
+                log.input_line();
+            }
+        }
+
+        Ok(available)
+    }
+
+    fn consume(&mut self, amount: usize) {
+        // The bytes are still buffered, so this doesn't block
+        if let Ok(buffered) = self.inner.fill_buf() {
+            let mut log = self.log.borrow_mut();
+
+            for &byte in &buffered[..amount.min(buffered.len())] {
+                log.partial_input.push(byte);
+
+                if byte == b'\n' {
+                    log.input_line();
+                }
+            }
+        }
+
+        self.inner.consume(amount);
+    }
+}
+
+/// Output that's recorded as it's written, a line at a time
+#[derive(Debug)]
+pub struct RecordingOutput<W> {
+    inner: W,
+    log: Rc<RefCell<Log>>,
+}
+
+impl<W: Write> Write for RecordingOutput<W> {
+    fn write(&mut self, buf: &[u8]) -> io::Result<usize> {
+        let written = self.inner.write(buf)?;
+
+        let mut log = self.log.borrow_mut();
+        for &byte in &buf[..written] {
+            log.partial_output.push(byte);
+
+            if byte == b'\n' {
+                log.output_line();
+            }
+        }
+
+        Ok(written)
+    }
+
+    fn flush(&mut self) -> io::Result<()> {
+        self.inner.flush()
+    }
+}
+
+pub fn write_events<W: Write>(mut output: W, events: &[Event]) -> io::Result<()> {
+    for event in events {
+        serde_json::to_writer(&mut output, event)?;
+        writeln!(output)?;
+    }
+
+    output.flush()
+}
+
+pub fn load_events(path: impl AsRef<Path>) -> Result<Vec<Event>, ReplayError> {
+    read_events(BufReader::new(File::open(path)?))
+}
+
+/// Read a transcript. Unlike the score table, a transcript is all-or-nothing: a line that
+/// doesn't parse is an error, since replaying around it would prove nothing.
+pub fn read_events<R: BufRead>(input: R) -> Result<Vec<Event>, ReplayError> {
+    let mut events = Vec::new();
+
+    for (index, line) in input.lines().enumerate() {
+        let line = line.map_err(ReplayError::Io)?;
+
+        if line.trim().is_empty() {
+            continue;
+        }
+
+        let event = serde_json::from_str(&line)
+            .map_err(|err| ReplayError::Parse { line: index + 1, message: err.to_string() })?;
+
+        events.push(event);
+    }
+
+    Ok(events)
+}
+
+/// Why a replay didn't match its transcript
+#[derive(Debug)]
+pub enum ReplayError {
+    Io(io::Error),
+    Parse { line: usize, message: String },
+    /// The transcript has to start with a `start` event
+    MissingStart,
+    /// The game said something other than what was recorded
+    Mismatch { line: usize, expected: String, actual: String },
+    /// The game stopped talking before the transcript did
+    MissingOutput { line: usize, expected: String },
+    /// The game said more than was recorded
+    ExtraOutput { actual: String },
+}
+
+impl fmt::Display for ReplayError {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        match self {
+            ReplayError::Io(err)                             => write!(f, "couldn't read transcript: {err}"),
+            ReplayError::Parse { line, message }             => write!(f, "line {line}: {message}"),
+            ReplayError::MissingStart                        => write!(f, "transcript doesn't begin with a start event"),
+            ReplayError::Mismatch { line, expected, actual } => write!(f, "line {line}: expected {expected:?}, got {actual:?}"),
+            ReplayError::MissingOutput { line, expected }    => write!(f, "line {line}: expected {expected:?}, got nothing"),
+            ReplayError::ExtraOutput { actual }              => write!(f, "unexpected output after the transcript ended: {actual:?}"),
+        }
+    }
+}
+
+impl std::error::Error for ReplayError {}
+
+impl From<io::Error> for ReplayError {
+    fn from(err: io::Error) -> Self {
+        ReplayError::Io(err)
+    }
+}
+
+// Hands recorded input to the game a line at a time, setting the clock to when each line arrived
+struct ReplayInput {
+    lines: VecDeque<(u64, Vec<u8>)>,
+    position: usize,
+    clock: ManualClock,
+}
+
+impl Read for ReplayInput {
+    fn read(&mut self, buf: &mut [u8]) -> io::Result<usize> {
+        let available = self.fill_buf()?;
+        let amount = available.len().min(buf.len());
+
+        buf[..amount].copy_from_slice(&available[..amount]);
+        self.consume(amount);
+
+        Ok(amount)
+    }
+}
+
+impl BufRead for ReplayInput {
+    fn fill_buf(&mut self) -> io::Result<&[u8]> {
+        match self.lines.front() {
+            Some((t_ms, line)) => {
+                if self.position == 0 {
+                    self.clock.set(Duration::from_millis(*t_ms));
+                }
+                Ok(&line[self.position..])
+            }
+            None => Ok(&[]),
+        }
+    }
+
+    fn consume(&mut self, amount: usize) {
+        self.position += amount;
+
+        if self.lines.front().is_some_and(|(_, line)| self.position >= line.len()) {
+            self.lines.pop_front();
+            self.position = 0;
+        }
+    }
+}
+
+/// Replay a transcript, returning the settings it was played with and how many lines of output matched
+pub fn replay(events: &[Event]) -> Result<(Settings, usize), ReplayError> {
+    let Some(Event::Start { settings, .. }) = events.first() else {
+        return Err(ReplayError::MissingStart);
+    };
+
+    let clock = ManualClock::new();
+
+    let input = ReplayInput {
+        lines: events.iter()
+            .filter_map(|event| match event {
+                Event::Input { t_ms, text } => Some((*t_ms, format!("{text}\n").into_bytes())),
+                _                           => None,
+            })
+            .collect(),
+        position: 0,
+        clock: clock.clone(),
+    };
+
+    let mut output = Vec::new();
+    writeln!(output, "{}", settings.greeting())?;
+
+    let mut game = settings.new_game().with_clock(clock);
+    game.play(input, &mut output)?;
+
+    let actual = String::from_utf8_lossy(&output);
+    let mut actual = actual.lines();
+    let mut matched = 0;
+
+    // Line numbers are the transcript's, so a mismatch is easy to find in the file
+    for (index, event) in events.iter().enumerate() {
+        let Event::Output { text: expected, .. } = event else {
+            continue;
+        };
+
+        match actual.next() {
+            Some(line) if line == expected => matched += 1,
+            Some(line) => {
+                return Err(ReplayError::Mismatch {
+                    line: index + 1,
+                    expected: expected.clone(),
+                    actual: line.to_string(),
+                });
+            }
+            None => return Err(ReplayError::MissingOutput { line: index + 1, expected: expected.clone() }),
+        }
+    }
+
+    if let Some(line) = actual.next() {
+        return Err(ReplayError::ExtraOutput { actual: line.to_string() });
+    }
+
+    Ok((*settings, matched))
+}
@@ -0,0 +1,191 @@
+use std::io;
+use std::time::Duration;
+
+use ratatui::crossterm::event::{self, Event, KeyCode, KeyEventKind, KeyModifiers};
+use ratatui::layout::{Constraint, Layout, Rect};
+use ratatui::style::{Color, Style, Stylize};
+use ratatui::text::{Line, Span};
+use ratatui::widgets::{Block, List, ListItem, Paragraph};
+use ratatui::{DefaultTerminal, Frame};
+
+use crate::{Feedback, GuessingGame};
+
+/* Terminal UI
+
+A full-screen front end for the same `GuessingGame` the line-based mode plays. The bar across
+the top is the whole range, with the part the secret could still be in lit up, so it narrows
+with every guess. Redraws a few times a second to keep the timer ticking.
+
+*/
+
+const TICK: Duration = Duration::from_millis(250);
+
+struct App<'a> {
+    game: &'a mut GuessingGame,
+    input: String,
+    message: Line<'static>,
+    quit: bool,
+}
+
+/// Play `game` full-screen until it's over (and the player presses a key) or they quit with Esc
+pub fn run(game: &mut GuessingGame) -> io::Result<()> {
+    let mut terminal = ratatui::try_init()?;
+
+    let result = App::new(game).run(&mut terminal);
+
+    ratatui::try_restore()?;
+
+    result
+}
+
+impl<'a> App<'a> {
+    fn new(game: &'a mut GuessingGame) -> Self {
+        App {
+            game,
+            input: String::new(),
+            message: Line::from("Type a guess and press Enter. Esc quits."),
+            quit: false,
+        }
+    }
+
+    fn run(mut self, terminal: &mut DefaultTerminal) -> io::Result<()> {
+        while !self.quit {
+            terminal.draw(|frame| self.draw(frame))?;
+
+            if !event::poll(TICK)? {
+                continue;
+            }
+
+            let Event::Key(key) = event::read()? else {
+                continue;
+            };
+
+            if key.kind != KeyEventKind::Press {
+                continue;
+            }
+
+            // Once the round is over, any key leaves
+            if self.game.is_over() {
+                break;
+            }
+
+            if key.modifiers.contains(KeyModifiers::CONTROL) && key.code == KeyCode::Char('c') {
+                self.quit = true;
+                continue;
+            }
+
+            match key.code {
+                KeyCode::Esc                                       => self.quit = true,
+                KeyCode::Char(c) if c.is_ascii_digit() || c == '-' => self.input.push(c),
+                KeyCode::Backspace                                 => { self.input.pop(); }
+                KeyCode::Enter                                     => self.submit(),
+                _                                                  => {}
+            }
+        }
+
+        Ok(())
+    }
+
+    fn submit(&mut self) {
+        let input = std::mem::take(&mut self.input);
+
+        self.message = match self.game.guess_str(&input) {
+            Ok(Feedback::Correct) => Line::from(format!("{input} is right! You win! Press any key.")).green().bold(),
+            Ok(feedback) if self.game.is_over() => {
+                Line::from(format!(
+                    "{} Out of attempts! The secret number was {}. Press any key.",
+                    feedback.message(self.game.range()),
+                    self.game.secret_number(),
+                )).red().bold()
+            }
+            Ok(feedback) => Line::from(feedback.message(self.game.range())),
+            Err(err)     => Line::from(err.to_string()).yellow(),
+        };
+    }
+
+    fn draw(&self, frame: &mut Frame) {
+        let [bar, status, history, message, input] = Layout::vertical([
+            Constraint::Length(3),
+            Constraint::Length(3),
+            Constraint::Min(3),
+            Constraint::Length(3),
+            Constraint::Length(3),
+        ]).areas(frame.area());
+
+        self.draw_bar(frame, bar);
+        self.draw_status(frame, status);
+        self.draw_history(frame, history);
+
+        frame.render_widget(Paragraph::new(self.message.clone()).block(Block::bordered()), message);
+        frame.render_widget(
+            Paragraph::new(format!("> {}", self.input)).block(Block::bordered().title(" Your guess ")),
+            input,
+        );
+    }
+
+    // The whole range, with the part that's still possible highlighted
+    fn draw_bar(&self, frame: &mut Frame, area: Rect) {
+        let range = self.game.range();
+        let remaining = self.game.remaining();
+        let width = area.width.saturating_sub(2).max(1) as u64;
+
+        let start = u64::from(*range.start());
+        let size = u64::from(*range.end()) - start + 1;
+
+        // Map each column to the slice of the range it covers
+        let column = |n: u32| (u64::from(n) - start) * width / size;
+        let (first, last) = (column(*remaining.start()), column(*remaining.end()));
+
+        let spans: Vec<Span> = (0..width)
+            .map(|col| {
+                if (first..=last).contains(&col) {
+                    Span::styled("█", Style::new().fg(Color::Green))
+                } else {
+                    Span::styled("░", Style::new().fg(Color::DarkGray))
+                }
+            })
+            .collect();
+
+        let title = format!(" {} to {} ", remaining.start(), remaining.end());
+
+        frame.render_widget(Paragraph::new(Line::from(spans)).block(Block::bordered().title(title)), area);
+    }
+
+    fn draw_status(&self, frame: &mut Frame, area: Rect) {
+        let attempts = match self.game.attempts_left() {
+            Some(left) => format!("{left} attempts left"),
+            None       => format!("{} attempts", self.game.attempts()),
+        };
+
+        let status = format!(
+            "{attempts}   {} invalid   {:.0}s",
+            self.game.invalid_attempts(),
+            self.game.elapsed().as_secs_f64().floor(),
+        );
+
+        let range = self.game.range();
+        let title = format!(" Guess the number between {} and {} ", range.start(), range.end());
+
+        frame.render_widget(Paragraph::new(status).block(Block::bordered().title(title)), area);
+    }
+
+    // Newest guess first, so it's always visible
+    fn draw_history(&self, frame: &mut Frame, area: Rect) {
+        let items: Vec<ListItem> = self.game.history().iter().rev()
+            .map(|&(guess, feedback)| {
+                let (text, color) = match feedback {
+                    Feedback::TooSmall => ("too small", Color::Cyan),
+                    Feedback::TooBig   => ("too big", Color::Magenta),
+                    Feedback::Correct  => ("correct!", Color::Green),
+                };
+
+                ListItem::new(Line::from(vec![
+                    Span::raw(format!("{guess:>6}  ")),
+                    Span::styled(text, Style::new().fg(color)),
+                ]))
+            })
+            .collect();
+
+        frame.render_widget(List::new(items).block(Block::bordered().title(" Guesses ")), area);
+    }
+}
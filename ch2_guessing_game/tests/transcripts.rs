@@ -0,0 +1,67 @@
+// Golden-file tests: transcripts recorded with `--record`, replayed against the current game
+
+use std::path::Path;
+
+use ch2_guessing_game::transcript::{self, load_events, Event, ReplayError};
+
+fn golden(name: &str) -> Vec<Event> {
+    let path = Path::new(env!("CARGO_MANIFEST_DIR")).join("tests/transcripts").join(name);
+
+    load_events(&path).unwrap_or_else(|err| panic!("{}: {err}", path.display()))
+}
+
+#[test]
+fn won_round_replays() {
+    let (settings, matched) = transcript::replay(&golden("win.jsonl")).unwrap();
+
+    assert_eq!(settings.seed, 7);
+    assert!(settings.hints);
+    assert_eq!(matched, 19);
+}
+
+#[test]
+fn lost_round_replays() {
+    let (settings, matched) = transcript::replay(&golden("loss.jsonl")).unwrap();
+
+    assert_eq!(settings.max_attempts, Some(2));
+    assert_eq!(matched, 9);
+}
+
+#[test]
+fn changed_output_is_a_mismatch() {
+    let mut events = golden("win.jsonl");
+
+    // Line 6 of the file is the game's answer to the first guess
+    let Event::Output { text, .. } = &mut events[5] else {
+        panic!("expected output on line 6, found {:?}", events[5]);
+    };
+    *text = String::from("Too small! (the number is between 1 and 100)");
+
+    match transcript::replay(&events) {
+        Err(ReplayError::Mismatch { line, expected, actual }) => {
+            assert_eq!(line, 6);
+            assert_eq!(expected, "Too small! (the number is between 1 and 100)");
+            assert_eq!(actual, "Too big! (the number is between 1 and 100)");
+        }
+        other => panic!("expected a mismatch, got {other:?}"),
+    }
+}
+
+#[test]
+fn different_seed_is_a_mismatch() {
+    let mut events = golden("loss.jsonl");
+
+    let Event::Start { settings, .. } = &mut events[0] else {
+        panic!("transcript doesn't start with a start event");
+    };
+    settings.seed = 8;
+
+    assert!(matches!(transcript::replay(&events), Err(ReplayError::Mismatch { .. })));
+}
+
+#[test]
+fn transcript_without_start_is_rejected() {
+    let events = golden("win.jsonl")[1..].to_vec();
+
+    assert!(matches!(transcript::replay(&events), Err(ReplayError::MissingStart)));
+}
@@ -0,0 +1,43 @@
+/* Convert
+
+Convert a temperature, length or mass to another unit:
+
+    cargo run --bin convert -- 100C F
+    cargo run --bin convert -- 5 km mi
+
+*/
+
+use std::env;
+use std::process;
+
+use ch3_common_programming_concepts::units::{Quantity, Unit, UnitError};
+
+fn convert(quantity: &str, to: &str) -> Result<(Quantity, Quantity), UnitError> {
+    let quantity: Quantity = quantity.parse()?;
+    let to: Unit = to.parse()?;
+
+    Ok((quantity, quantity.convert(to)?))
+}
+
+fn main() {
+
+    let args: Vec<String> = env::args().skip(1).collect();
+
+    // The unit can be stuck to the number ("100C") or a word of its own ("100 C")
+    let (quantity, to) = match args.as_slice() {
+        [quantity, to]    => (quantity.clone(), to),
+        [value, unit, to] => (format!("{value}{unit}"), to),
+        _                 => {
+            eprintln!("Usage: convert <quantity> <unit>, e.g. convert 100C F");
+            process::exit(2);
+        }
+    };
+
+    match convert(&quantity, to) {
+        Ok((from, to)) => println!("{from} = {to}"),
+        Err(err) => {
+            eprintln!("{err}");
+            process::exit(1);
+        }
+    }
+}
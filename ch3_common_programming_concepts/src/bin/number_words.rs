@@ -0,0 +1,38 @@
+/* Number words
+
+Turn numbers into words, or words into numbers:
+
+    cargo run --bin number_words -- 123
+    cargo run --bin number_words -- one hundred twenty-third
+
+*/
+
+use std::env;
+use std::process;
+
+use ch3_common_programming_concepts::number_words::{self, cardinal, ordinal};
+
+fn main() {
+
+    let input = env::args().skip(1).collect::<Vec<_>>().join(" ");
+
+    if input.is_empty() {
+        eprintln!("Usage: number_words <number or words>");
+        process::exit(2);
+    }
+
+    // Digits get written out, anything else gets read as words
+    if let Ok(n) = input.parse::<i128>() {
+        println!("{}\n{}", cardinal(n), ordinal(n));
+    } else if let Ok(n) = input.parse::<u128>() {
+        println!("{}\n{}", cardinal(n), ordinal(n));
+    } else {
+        match number_words::parse(&input) {
+            Ok(number) => println!("{number}"),
+            Err(err) => {
+                eprintln!("{err}");
+                process::exit(1);
+            }
+        }
+    }
+}
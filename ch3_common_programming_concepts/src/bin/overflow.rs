@@ -0,0 +1,94 @@
+/* Overflow
+
+What each of `wrapping_*`, `checked_*`, `saturating_*` and `overflowing_*` makes of an operation,
+for every integer type:
+
+    cargo run --bin overflow -- 250 + 10
+    cargo run --bin overflow -- 2 pow 100
+    cargo run --bin overflow -- neg -128
+
+`*` is best written `mul` (or quoted), so the shell doesn't expand it. Add `--type u8` (or any
+other integer type) to just see one.
+
+*/
+
+use std::env;
+use std::process;
+
+use ch3_common_programming_concepts::overflow::{self, Op, OverflowError, Row, Width};
+
+const HEADINGS: [&str; 5] = ["type", "wrapping", "checked", "saturating", "overflowing"];
+
+fn explore(args: &[String]) -> Result<Vec<Result<Row, OverflowError>>, OverflowError> {
+    let mut args = args.to_vec();
+    let mut width = None;
+
+    if let Some(i) = args.iter().position(|arg| arg == "--type") {
+        let name = args.get(i + 1).cloned().unwrap_or_default();
+        width = Some(name.parse::<Width>()?);
+        args.drain(i..(i + 2).min(args.len()));
+    }
+
+    // `neg 5` has the operation first, everything else has it in the middle
+    let (op, lhs, rhs) = match args.as_slice() {
+        [op, lhs]      => (op.parse::<Op>()?, lhs.clone(), None),
+        [lhs, op, rhs] => (op.parse::<Op>()?, lhs.clone(), Some(rhs.as_str())),
+        _              => {
+            eprintln!("Usage: overflow <lhs> <op> <rhs> [--type <type>], or overflow neg <n>");
+            process::exit(2);
+        }
+    };
+
+    match width {
+        Some(width) => Ok(vec![overflow::explore(width, op, &lhs, rhs)]),
+        None        => overflow::explore_all(op, &lhs, rhs),
+    }
+}
+
+fn main() {
+
+    let args: Vec<String> = env::args().skip(1).collect();
+
+    let rows = explore(&args).unwrap_or_else(|err| {
+        eprintln!("{err}");
+        process::exit(1);
+    });
+
+    let table: Vec<Vec<String>> = rows.iter()
+        .map(|row| match row {
+            Ok(row) => vec![
+                row.width.to_string(),
+                row.wrapping.to_string(),
+                row.checked.to_string(),
+                row.saturating.to_string(),
+                row.overflowing.to_string(),
+            ],
+            // Operands too big for the type, which isn't worth a column each
+            Err(OverflowError::DoesNotFit { width, .. }) => vec![width.to_string(), String::from("(doesn't fit)")],
+            Err(err) => vec![String::from("?"), err.to_string()],
+        })
+        .collect();
+
+    // Each column as wide as its widest cell
+    let widths: Vec<usize> = (0..HEADINGS.len())
+        .map(|column| {
+            table.iter()
+                .filter(|cells| cells.len() == HEADINGS.len())
+                .map(|cells| cells[column].len())
+                .chain([HEADINGS[column].len()])
+                .max()
+                .unwrap_or(0)
+        })
+        .collect();
+
+    let heading = HEADINGS.map(String::from);
+
+    for cells in [&heading[..]].into_iter().chain(table.iter().map(Vec::as_slice)) {
+        let line: Vec<String> = cells.iter()
+            .zip(&widths)
+            .map(|(cell, width)| format!("{cell:<width$}"))
+            .collect();
+
+        println!("{}", line.join("  ").trim_end());
+    }
+}
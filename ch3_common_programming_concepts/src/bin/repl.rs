@@ -0,0 +1,56 @@
+/* REPL
+
+Read, evaluate, print, loop, for the little expression language in `expr.rs`:
+
+    $ cargo run --bin repl
+    > let b = { let a = 5; a + 2 };
+    > b * 6
+    42
+
+Definitions stick around from one line to the next. Unfinished input (an open brace, say) keeps
+reading on the next line. Ctrl-D quits.
+
+*/
+
+use std::io::{self, BufRead, Write};
+
+use ch3_common_programming_concepts::expr::{self, Interpreter, Value};
+
+fn prompt(continuing: bool) -> io::Result<()> {
+    print!("{}", if continuing { ". " } else { "> " });
+    io::stdout().flush()
+}
+
+fn main() -> io::Result<()> {
+
+    let mut interpreter = Interpreter::new();
+    let mut source = String::new();
+
+    prompt(false)?;
+
+    for line in io::stdin().lock().lines() {
+        source.push_str(&line?);
+        source.push('\n');
+
+        match expr::parse(&source) {
+            // Keep reading until the input makes sense
+            Err(err) if err.incomplete => {
+                prompt(true)?;
+                continue;
+            }
+            Err(err) => println!("parse error: {err}"),
+            Ok(program) => match interpreter.run(&program) {
+                Ok(Value::Unit) => {}
+                Ok(value)       => println!("{value}"),
+                Err(err)        => println!("error: {err}"),
+            },
+        }
+
+        source.clear();
+        prompt(false)?;
+    }
+
+    println!();
+
+    Ok(())
+}
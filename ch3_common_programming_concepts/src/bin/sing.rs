@@ -0,0 +1,39 @@
+/* Sing
+
+Print one of the songs from `songs.rs` by name:
+
+    cargo run --bin sing -- 99-bottles
+
+Without a name (or with one it doesn't know), lists the songs there are.
+
+*/
+
+use std::env;
+use std::process;
+
+use ch3_common_programming_concepts::songs::{self, SONGS};
+
+fn list_songs() {
+    println!("Songs:");
+
+    for song in SONGS {
+        println!("  {:<15} {} ({} verses)", song.name, song.title, song.len());
+    }
+}
+
+fn main() {
+
+    let Some(name) = env::args().nth(1) else {
+        list_songs();
+        return;
+    };
+
+    match songs::by_name(&name) {
+        Some(song) => println!("{}\n\n{}", song.title, song.lyrics()),
+        None => {
+            eprintln!("No song called \"{name}\".");
+            list_songs();
+            process::exit(1);
+        }
+    }
+}
@@ -0,0 +1,140 @@
+use std::error::Error;
+use std::fmt;
+
+/* Expressions
+
+Chapter 3 makes a point of blocks being expressions: `let b = { let a = 5; a + 2 };` sets `b` to 7,
+because a block's value is its last expression (as long as there's no `;` after it). This is a
+tiny language built around that idea, so it can be run and poked at:
+
+    fn fib(n) { if n < 2 { n } else { fib(n - 1) + fib(n - 2) } }
+    let b = { let a = 5; a + 2 };
+    fib(b)
+
+It has integers, bools, `let` (with shadowing), blocks, `if`/`else` (conditions must be bools,
+like in Rust), functions, and the usual arithmetic, comparison and logic operators. Overflow and
+division by zero are errors.
+
+Source goes through the `lexer` into tokens, the `parser` (a Pratt parser) into a syntax tree, and
+the `interpreter` walks the tree to get a value.
+
+*/
+
+pub mod interpreter;
+pub mod lexer;
+pub mod parser;
+
+pub use interpreter::{Interpreter, Value};
+
+/// Source code that doesn't parse
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct ParseError {
+    pub message: String,
+    /// Byte offset into the source where things went wrong
+    pub offset: usize,
+    /// Whether the source just stopped too soon, e.g. in the middle of a block. More input might
+    /// fix that, which is how the REPL knows to keep reading.
+    pub incomplete: bool,
+}
+
+impl ParseError {
+    pub(crate) fn new(message: String, offset: usize) -> Self {
+        ParseError { message, offset, incomplete: false }
+    }
+
+    pub(crate) fn incomplete(message: String, offset: usize) -> Self {
+        ParseError { message, offset, incomplete: true }
+    }
+}
+
+impl fmt::Display for ParseError {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        write!(f, "{} (at {})", self.message, self.offset)
+    }
+}
+
+impl Error for ParseError {}
+
+/// A program that parses, but goes wrong when it runs
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum EvalError {
+    UnknownVariable(String),
+    /// Expected one type, found a value of another
+    TypeMismatch { expected: &'static str, found: &'static str },
+    NotAFunction(String, &'static str),
+    WrongArgumentCount { function: String, expected: usize, found: usize },
+    DivideByZero,
+    Overflow,
+    /// Calls nested more than `MAX_CALL_DEPTH` deep (which is probably endless recursion), or
+    /// expressions more than `MAX_EVAL_DEPTH` deep
+    TooDeep,
+}
+
+impl EvalError {
+    pub(crate) fn mismatch(expected: &'static str, found: &Value) -> Self {
+        EvalError::TypeMismatch { expected, found: found.type_name() }
+    }
+}
+
+impl fmt::Display for EvalError {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        match self {
+            EvalError::UnknownVariable(name)            => write!(f, "cannot find `{name}` in this scope"),
+            EvalError::TypeMismatch { expected, found } => write!(f, "expected {expected}, found {found}"),
+            EvalError::NotAFunction(name, found)        => write!(f, "`{name}` is {found}, not a function"),
+            EvalError::WrongArgumentCount { function, expected, found } => {
+                let plural = |n: usize| if n == 1 { "" } else { "s" };
+                write!(f, "`{function}` takes {expected} argument{} but {found} were supplied", plural(*expected))
+            }
+            EvalError::DivideByZero                     => write!(f, "attempt to divide by zero"),
+            EvalError::Overflow                         => write!(f, "attempt to overflow"),
+            EvalError::TooDeep                          => {
+                write!(f, "nested too deep (more than {} calls or {} expressions)", interpreter::MAX_CALL_DEPTH, interpreter::MAX_EVAL_DEPTH)
+            }
+        }
+    }
+}
+
+impl Error for EvalError {}
+
+/// Either way a program can fail
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum ExprError {
+    Parse(ParseError),
+    Eval(EvalError),
+}
+
+impl fmt::Display for ExprError {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        match self {
+            ExprError::Parse(err) => write!(f, "parse error: {err}"),
+            ExprError::Eval(err)  => write!(f, "error: {err}"),
+        }
+    }
+}
+
+impl Error for ExprError {}
+
+impl From<ParseError> for ExprError {
+    fn from(err: ParseError) -> Self {
+        ExprError::Parse(err)
+    }
+}
+
+impl From<EvalError> for ExprError {
+    fn from(err: EvalError) -> Self {
+        ExprError::Eval(err)
+    }
+}
+
+/// Turn source code into a syntax tree
+pub fn parse(source: &str) -> Result<parser::Block, ParseError> {
+    let tokens = lexer::tokenize(source)?;
+
+    parser::Parser::new(tokens, source.len()).program()
+}
+
+/// Run a program on a fresh interpreter and return its value
+pub fn eval(source: &str) -> Result<Value, ExprError> {
+    Ok(Interpreter::new().run(&parse(source)?)?)
+}
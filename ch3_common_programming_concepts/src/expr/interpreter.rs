@@ -0,0 +1,359 @@
+use std::cell::RefCell;
+use std::collections::HashMap;
+use std::fmt;
+use std::rc::Rc;
+
+use super::parser::{BinaryOp, Block, Expr, Function, Stmt, UnaryOp};
+use super::EvalError;
+
+/// What an expression evaluates to
+#[derive(Debug, Clone, PartialEq)]
+pub enum Value {
+    Int(i64),
+    Bool(bool),
+    /// What blocks ending in `;` (and `if`s without an `else`) evaluate to
+    Unit,
+    Function(Closure),
+}
+
+impl Value {
+    /// The name of the value's type, for error messages
+    pub fn type_name(&self) -> &'static str {
+        match self {
+            Value::Int(_)      => "integer",
+            Value::Bool(_)     => "bool",
+            Value::Unit        => "()",
+            Value::Function(_) => "function",
+        }
+    }
+}
+
+impl fmt::Display for Value {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        match self {
+            Value::Int(n)            => write!(f, "{n}"),
+            Value::Bool(b)           => write!(f, "{b}"),
+            Value::Unit              => write!(f, "()"),
+            Value::Function(closure) => write!(f, "fn {}({})", closure.function.name, closure.function.params.join(", ")),
+        }
+    }
+}
+
+/// A function and the scope it was defined in
+#[derive(Debug, Clone)]
+pub struct Closure {
+    function: Rc<Function>,
+    scope: Rc<Scope>,
+}
+
+impl PartialEq for Closure {
+    // The same definition in the same scope
+    fn eq(&self, other: &Self) -> bool {
+        Rc::ptr_eq(&self.function, &other.function) && Rc::ptr_eq(&self.scope, &other.scope)
+    }
+}
+
+/// Variables defined in a block, and the scope around it
+#[derive(Debug, Default)]
+pub struct Scope {
+    vars: RefCell<HashMap<String, Value>>,
+    parent: Option<Rc<Scope>>,
+}
+
+impl Scope {
+    fn child(parent: &Rc<Scope>) -> Rc<Scope> {
+        Rc::new(Scope { vars: RefCell::default(), parent: Some(Rc::clone(parent)) })
+    }
+
+    fn get(&self, name: &str) -> Option<Value> {
+        match self.vars.borrow().get(name) {
+            Some(value) => Some(value.clone()),
+            None        => self.parent.as_ref()?.get(name),
+        }
+    }
+
+    // `let` always makes a new variable, shadowing any with the same name
+    fn define(&self, name: &str, value: Value) {
+        self.vars.borrow_mut().insert(name.to_string(), value);
+    }
+}
+
+/// Deepest a chain of calls can go before it's stopped, instead of overflowing the real stack
+pub const MAX_CALL_DEPTH: usize = 200;
+
+/// Deepest expressions can be evaluated inside each other, counting through calls, before it's
+/// stopped for the same reason. Syntax trees built by hand don't go through the parser's limit.
+pub const MAX_EVAL_DEPTH: usize = 1000;
+
+/// A tree-walking interpreter. Variables and functions defined at the top level stick around
+/// between calls to `run`, which is what lets the REPL build on earlier lines.
+#[derive(Debug)]
+pub struct Interpreter {
+    globals: Rc<Scope>,
+    depth: usize,
+    /// How many expressions deep evaluation is, calls included
+    nesting: usize,
+}
+
+impl Default for Interpreter {
+    fn default() -> Self {
+        Interpreter::new()
+    }
+}
+
+impl Interpreter {
+    pub fn new() -> Self {
+        Interpreter { globals: Rc::default(), depth: 0, nesting: 0 }
+    }
+
+    /// Run a program, defining its `let`s and `fn`s at the top level
+    pub fn run(&mut self, program: &Block) -> Result<Value, EvalError> {
+        let globals = Rc::clone(&self.globals);
+
+        self.block_in(program, &globals)
+    }
+
+    fn block(&mut self, block: &Block, scope: &Rc<Scope>) -> Result<Value, EvalError> {
+        self.block_in(block, &Scope::child(scope))
+    }
+
+    // Run the block's statements right in `scope`, without a scope of its own
+    fn block_in(&mut self, block: &Block, scope: &Rc<Scope>) -> Result<Value, EvalError> {
+        for stmt in &block.stmts {
+            match stmt {
+                Stmt::Let { name, value } => {
+                    let value = self.eval(value, scope)?;
+                    scope.define(name, value);
+                }
+                Stmt::Fn(function) => {
+                    let closure = Closure { function: Rc::clone(function), scope: Rc::clone(scope) };
+                    scope.define(&function.name, Value::Function(closure));
+                }
+                Stmt::Expr(expr) => {
+                    self.eval(expr, scope)?;
+                }
+            }
+        }
+
+        match &block.tail {
+            Some(tail) => self.eval(tail, scope),
+            None       => Ok(Value::Unit),
+        }
+    }
+
+    fn eval(&mut self, expr: &Expr, scope: &Rc<Scope>) -> Result<Value, EvalError> {
+        if self.nesting >= MAX_EVAL_DEPTH {
+            return Err(EvalError::TooDeep);
+        }
+
+        self.nesting += 1;
+        let result = self.eval_nested(expr, scope);
+        self.nesting -= 1;
+
+        result
+    }
+
+    // `eval`, without counting how deep it is
+    fn eval_nested(&mut self, expr: &Expr, scope: &Rc<Scope>) -> Result<Value, EvalError> {
+        match expr {
+            Expr::Int(n)    => Ok(Value::Int(*n)),
+            Expr::Bool(b)   => Ok(Value::Bool(*b)),
+            Expr::Var(name) => scope.get(name).ok_or_else(|| EvalError::UnknownVariable(name.clone())),
+            Expr::Unary { op, operand } => {
+                let value = self.eval(operand, scope)?;
+
+                match (op, value) {
+                    (UnaryOp::Negate, Value::Int(n)) => n.checked_neg().map(Value::Int).ok_or(EvalError::Overflow),
+                    (UnaryOp::Not, Value::Bool(b))   => Ok(Value::Bool(!b)),
+                    (UnaryOp::Negate, value)         => Err(EvalError::mismatch("integer", &value)),
+                    (UnaryOp::Not, value)            => Err(EvalError::mismatch("bool", &value)),
+                }
+            }
+            Expr::Binary { op, lhs, rhs } => self.binary(*op, lhs, rhs, scope),
+            Expr::Block(block) => self.block(block, scope),
+            Expr::If { condition, then, otherwise } => {
+                // Conditions have to be bools, there's no "truthy" here either
+                match self.eval(condition, scope)? {
+                    Value::Bool(true)  => self.block(then, scope),
+                    Value::Bool(false) => match otherwise {
+                        Some(otherwise) => self.eval(otherwise, scope),
+                        None            => Ok(Value::Unit),
+                    },
+                    value              => Err(EvalError::mismatch("bool", &value)),
+                }
+            }
+            Expr::Call { function, args } => {
+                let closure = match scope.get(function) {
+                    Some(Value::Function(closure)) => closure,
+                    Some(value)                    => return Err(EvalError::NotAFunction(function.clone(), value.type_name())),
+                    None                           => return Err(EvalError::UnknownVariable(function.clone())),
+                };
+
+                let args = args.iter().map(|arg| self.eval(arg, scope)).collect::<Result<Vec<_>, _>>()?;
+
+                self.call(&closure, args)
+            }
+        }
+    }
+
+    fn call(&mut self, closure: &Closure, args: Vec<Value>) -> Result<Value, EvalError> {
+        let function = &closure.function;
+
+        if args.len() != function.params.len() {
+            return Err(EvalError::WrongArgumentCount {
+                function: function.name.clone(),
+                expected: function.params.len(),
+                found: args.len(),
+            });
+        }
+
+        if self.depth >= MAX_CALL_DEPTH {
+            return Err(EvalError::TooDeep);
+        }
+
+        // A fresh scope inside the one the function was defined in, so it can call itself
+        let scope = Scope::child(&closure.scope);
+
+        for (param, arg) in function.params.iter().zip(args) {
+            scope.define(param, arg);
+        }
+
+        self.depth += 1;
+        let result = self.block_in(&function.body, &scope);
+        self.depth -= 1;
+
+        result
+    }
+
+    fn binary(&mut self, op: BinaryOp, lhs: &Expr, rhs: &Expr, scope: &Rc<Scope>) -> Result<Value, EvalError> {
+        let lhs = self.eval(lhs, scope)?;
+
+        // `&&` and `||` only look at the right-hand side if they have to
+        if let (BinaryOp::And | BinaryOp::Or, Value::Bool(left)) = (op, &lhs) {
+            if (op == BinaryOp::And) != *left {
+                return Ok(Value::Bool(*left));
+            }
+
+            return match self.eval(rhs, scope)? {
+                Value::Bool(right) => Ok(Value::Bool(right)),
+                value              => Err(EvalError::mismatch("bool", &value)),
+            };
+        }
+
+        let rhs = self.eval(rhs, scope)?;
+
+        let (a, b) = match (op, &lhs, &rhs) {
+            (BinaryOp::Equal, ..)                     => return Ok(Value::Bool(lhs == rhs)),
+            (BinaryOp::NotEqual, ..)                  => return Ok(Value::Bool(lhs != rhs)),
+            (BinaryOp::And | BinaryOp::Or, ..)        => return Err(EvalError::mismatch("bool", &lhs)),
+            (_, Value::Int(a), Value::Int(b))         => (*a, *b),
+            (_, Value::Int(_), value) | (_, value, _) => return Err(EvalError::mismatch("integer", value)),
+        };
+
+        if matches!(op, BinaryOp::Divide | BinaryOp::Remainder) && b == 0 {
+            return Err(EvalError::DivideByZero);
+        }
+
+        // Overflow is an error, like a debug build of Rust, rather than quietly wrapping
+        let value = match op {
+            BinaryOp::Add          => Value::Int(a.checked_add(b).ok_or(EvalError::Overflow)?),
+            BinaryOp::Subtract     => Value::Int(a.checked_sub(b).ok_or(EvalError::Overflow)?),
+            BinaryOp::Multiply     => Value::Int(a.checked_mul(b).ok_or(EvalError::Overflow)?),
+            BinaryOp::Divide       => Value::Int(a.checked_div(b).ok_or(EvalError::Overflow)?),
+            BinaryOp::Remainder    => Value::Int(a.checked_rem(b).ok_or(EvalError::Overflow)?),
+            BinaryOp::Less         => Value::Bool(a < b),
+            BinaryOp::LessEqual    => Value::Bool(a <= b),
+            BinaryOp::Greater      => Value::Bool(a > b),
+            BinaryOp::GreaterEqual => Value::Bool(a >= b),
+            BinaryOp::Equal | BinaryOp::NotEqual | BinaryOp::And | BinaryOp::Or => unreachable!("handled above"),
+        };
+
+        Ok(value)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::thread;
+
+    use super::*;
+    use crate::expr::{eval, parse, ExprError};
+
+    // The limits leave room to spare on the main thread's 8 MiB stack, which is where the REPL runs,
+    // but test threads only get 2 MiB
+    fn with_main_stack<T: Send + 'static>(run: impl FnOnce() -> T + Send + 'static) -> T {
+        thread::Builder::new().stack_size(8 << 20).spawn(run).unwrap().join().unwrap()
+    }
+
+    fn value(source: &str) -> Value {
+        eval(source).unwrap()
+    }
+
+    fn error(source: &str) -> EvalError {
+        match eval(source) {
+            Err(ExprError::Eval(err)) => err,
+            other                     => panic!("expected an EvalError, got {other:?}"),
+        }
+    }
+
+    #[test]
+    fn blocks_are_expressions() {
+        assert_eq!(value("let b = { let a = 5; a + 2 }; b"), Value::Int(7));
+        assert_eq!(value("{ 1; }"), Value::Unit);
+        assert_eq!(value("let x = 5; let x = x + 1; { let x = x * 2; x }"), Value::Int(12));
+    }
+
+    #[test]
+    fn functions_can_call_themselves() {
+        assert_eq!(value("fn fib(n) { if n < 2 { n } else { fib(n - 1) + fib(n - 2) } } fib(10)"), Value::Int(55));
+    }
+
+    #[test]
+    fn errors() {
+        assert_eq!(error("if 1 { 2 }"), EvalError::TypeMismatch { expected: "bool", found: "integer" });
+        assert_eq!(error("1 / 0"), EvalError::DivideByZero);
+        assert_eq!(error("9223372036854775807 + 1"), EvalError::Overflow);
+        assert_eq!(error("y"), EvalError::UnknownVariable(String::from("y")));
+        assert_eq!(error("let f = 1; f()"), EvalError::NotAFunction(String::from("f"), "integer"));
+    }
+
+    #[test]
+    fn long_chains_of_operators() {
+        assert_eq!(value(&vec!["1"; 100].join(" + ")), Value::Int(100));
+        assert!(matches!(eval(&vec!["1"; 1001].join(" + ")), Err(ExprError::Parse(_))));
+    }
+
+    #[test]
+    fn endless_recursion_is_too_deep() {
+        with_main_stack(|| {
+            assert_eq!(error("fn f(n) { f(n + 1) } f(0)"), EvalError::TooDeep);
+            assert_eq!(value("fn count(n) { if n == 0 { 0 } else { 1 + count(n - 1) } } count(150)"), Value::Int(150));
+        });
+    }
+
+    #[test]
+    fn deep_expressions_inside_recursion_are_too_deep() {
+        // Fine on its own, but not 100 calls deep
+        let nested = format!("{}f(n - 1)", "- ".repeat(100));
+        let source = format!("fn f(n) {{ if n == 0 {{ 0 }} else {{ {nested} }} }} f(100)");
+
+        assert_eq!(with_main_stack(move || error(&source)), EvalError::TooDeep);
+    }
+
+    #[test]
+    fn trees_built_by_hand_are_limited_too() {
+        with_main_stack(|| {
+            let deep = (0..2 * MAX_EVAL_DEPTH).fold(Expr::Int(1), |operand, _| Expr::Unary {
+                op: UnaryOp::Not,
+                operand: Box::new(operand),
+            });
+            let program = Block { stmts: Vec::new(), tail: Some(Box::new(deep)) };
+            let mut interpreter = Interpreter::new();
+
+            assert_eq!(interpreter.run(&program), Err(EvalError::TooDeep));
+
+            // Nothing's left half-counted afterwards
+            assert_eq!(interpreter.run(&parse("let x = 2; x * 3").unwrap()), Ok(Value::Int(6)));
+        });
+    }
+}
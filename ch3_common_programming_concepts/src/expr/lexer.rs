@@ -0,0 +1,226 @@
+use std::fmt;
+
+use super::ParseError;
+
+/// One piece of source code
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum Token {
+    Int(i64),
+    Ident(String),
+    // Keywords
+    Let,
+    Fn,
+    If,
+    Else,
+    True,
+    False,
+    // Punctuation
+    LeftParen,
+    RightParen,
+    LeftBrace,
+    RightBrace,
+    Comma,
+    Semicolon,
+    // Operators
+    Plus,
+    Minus,
+    Star,
+    Slash,
+    Percent,
+    Bang,
+    Assign,
+    Equal,
+    NotEqual,
+    Less,
+    LessEqual,
+    Greater,
+    GreaterEqual,
+    And,
+    Or,
+}
+
+impl fmt::Display for Token {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        let text = match self {
+            Token::Int(n)       => return write!(f, "{n}"),
+            Token::Ident(name)  => return write!(f, "{name}"),
+            Token::Let          => "let",
+            Token::Fn           => "fn",
+            Token::If           => "if",
+            Token::Else         => "else",
+            Token::True         => "true",
+            Token::False        => "false",
+            Token::LeftParen    => "(",
+            Token::RightParen   => ")",
+            Token::LeftBrace    => "{",
+            Token::RightBrace   => "}",
+            Token::Comma        => ",",
+            Token::Semicolon    => ";",
+            Token::Plus         => "+",
+            Token::Minus        => "-",
+            Token::Star         => "*",
+            Token::Slash        => "/",
+            Token::Percent      => "%",
+            Token::Bang         => "!",
+            Token::Assign       => "=",
+            Token::Equal        => "==",
+            Token::NotEqual     => "!=",
+            Token::Less         => "<",
+            Token::LessEqual    => "<=",
+            Token::Greater      => ">",
+            Token::GreaterEqual => ">=",
+            Token::And          => "&&",
+            Token::Or           => "||",
+        };
+
+        write!(f, "{text}")
+    }
+}
+
+/// A token and the byte offset it starts at, for error messages
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Spanned {
+    pub token: Token,
+    pub offset: usize,
+}
+
+/// Split source code into tokens. `//` comments run to the end of the line.
+pub fn tokenize(source: &str) -> Result<Vec<Spanned>, ParseError> {
+    let mut tokens = Vec::new();
+    let mut chars = source.char_indices().peekable();
+
+    while let Some((offset, c)) = chars.next() {
+        // Two-character operators peek at the next character
+        let mut followed_by = |next: char| chars.next_if(|&(_, c)| c == next).is_some();
+
+        let token = match c {
+            c if c.is_whitespace() => continue,
+            '/' if followed_by('/') => {
+                while chars.next_if(|&(_, c)| c != '\n').is_some() {}
+                continue;
+            }
+            '(' => Token::LeftParen,
+            ')' => Token::RightParen,
+            '{' => Token::LeftBrace,
+            '}' => Token::RightBrace,
+            ',' => Token::Comma,
+            ';' => Token::Semicolon,
+            '+' => Token::Plus,
+            '-' => Token::Minus,
+            '*' => Token::Star,
+            '/' => Token::Slash,
+            '%' => Token::Percent,
+            '!' if followed_by('=') => Token::NotEqual,
+            '!' => Token::Bang,
+            '=' if followed_by('=') => Token::Equal,
+            '=' => Token::Assign,
+            '<' if followed_by('=') => Token::LessEqual,
+            '<' => Token::Less,
+            '>' if followed_by('=') => Token::GreaterEqual,
+            '>' => Token::Greater,
+            '&' if followed_by('&') => Token::And,
+            '|' if followed_by('|') => Token::Or,
+            c if c.is_ascii_digit() => {
+                let mut digits = String::from(c);
+
+                while let Some((_, c)) = chars.next_if(|&(_, c)| c.is_ascii_digit() || c == '_') {
+                    digits.push(c);
+                }
+
+                let n = digits.replace('_', "").parse().map_err(|_| ParseError::new(format!("{digits} is too big"), offset))?;
+                Token::Int(n)
+            }
+            c if c.is_alphabetic() || c == '_' => {
+                let mut word = String::from(c);
+
+                while let Some((_, c)) = chars.next_if(|&(_, c)| c.is_alphanumeric() || c == '_') {
+                    word.push(c);
+                }
+
+                match word.as_str() {
+                    "let"   => Token::Let,
+                    "fn"    => Token::Fn,
+                    "if"    => Token::If,
+                    "else"  => Token::Else,
+                    "true"  => Token::True,
+                    "false" => Token::False,
+                    _       => Token::Ident(word),
+                }
+            }
+            c => return Err(ParseError::new(format!("unexpected character '{c}'"), offset)),
+        };
+
+        tokens.push(Spanned { token, offset });
+    }
+
+    Ok(tokens)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn tokens(source: &str) -> Vec<Token> {
+        tokenize(source).unwrap().into_iter().map(|spanned| spanned.token).collect()
+    }
+
+    #[test]
+    fn two_character_operators() {
+        assert_eq!(tokens("== != <= >= && ||"), [Token::Equal, Token::NotEqual, Token::LessEqual, Token::GreaterEqual, Token::And, Token::Or]);
+        assert_eq!(tokens("= ! < > a=b"), [
+            Token::Assign,
+            Token::Bang,
+            Token::Less,
+            Token::Greater,
+            Token::Ident(String::from("a")),
+            Token::Assign,
+            Token::Ident(String::from("b")),
+        ]);
+        assert_eq!(tokens("!!="), [Token::Bang, Token::NotEqual]);
+    }
+
+    #[test]
+    fn comments_run_to_the_end_of_the_line() {
+        assert_eq!(tokens("1 // two\n/ 3 // four"), [Token::Int(1), Token::Slash, Token::Int(3)]);
+        assert_eq!(tokens("// nothing at all"), []);
+    }
+
+    #[test]
+    fn numbers_can_have_underscores() {
+        assert_eq!(tokens("1_000_000 12__3 4_"), [Token::Int(1_000_000), Token::Int(123), Token::Int(4)]);
+
+        // Not at the start, though, where it's a name
+        assert_eq!(tokens("_1"), [Token::Ident(String::from("_1"))]);
+    }
+
+    #[test]
+    fn keywords_and_offsets() {
+        let spanned = tokenize("let x = fn_1;").unwrap();
+
+        assert_eq!(spanned, [
+            Spanned { token: Token::Let, offset: 0 },
+            Spanned { token: Token::Ident(String::from("x")), offset: 4 },
+            Spanned { token: Token::Assign, offset: 6 },
+            Spanned { token: Token::Ident(String::from("fn_1")), offset: 8 },
+            Spanned { token: Token::Semicolon, offset: 12 },
+        ]);
+    }
+
+    #[test]
+    fn too_big_integers() {
+        assert_eq!(tokens("9_223_372_036_854_775_807"), [Token::Int(i64::MAX)]);
+        assert_eq!(
+            tokenize("1 + 9_223_372_036_854_775_808"),
+            Err(ParseError::new(String::from("9_223_372_036_854_775_808 is too big"), 4)),
+        );
+    }
+
+    #[test]
+    fn unexpected_characters_say_where_they_are() {
+        assert_eq!(tokenize("1 + #"), Err(ParseError::new(String::from("unexpected character '#'"), 4)));
+        assert_eq!(tokenize("a & b"), Err(ParseError::new(String::from("unexpected character '&'"), 2)));
+
+        // Offsets are in bytes, not characters
+        assert_eq!(tokenize("é @"), Err(ParseError::new(String::from("unexpected character '@'"), 3)));
+    }
+}
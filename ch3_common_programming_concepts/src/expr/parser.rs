@@ -0,0 +1,487 @@
+use std::rc::Rc;
+
+use super::lexer::{Spanned, Token};
+use super::ParseError;
+
+/// Operators that take one operand
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum UnaryOp {
+    Negate,
+    Not,
+}
+
+/// Operators that take two operands
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum BinaryOp {
+    Add,
+    Subtract,
+    Multiply,
+    Divide,
+    Remainder,
+    Equal,
+    NotEqual,
+    Less,
+    LessEqual,
+    Greater,
+    GreaterEqual,
+    And,
+    Or,
+}
+
+impl BinaryOp {
+    fn from_token(token: &Token) -> Option<BinaryOp> {
+        let op = match token {
+            Token::Plus         => BinaryOp::Add,
+            Token::Minus        => BinaryOp::Subtract,
+            Token::Star         => BinaryOp::Multiply,
+            Token::Slash        => BinaryOp::Divide,
+            Token::Percent      => BinaryOp::Remainder,
+            Token::Equal        => BinaryOp::Equal,
+            Token::NotEqual     => BinaryOp::NotEqual,
+            Token::Less         => BinaryOp::Less,
+            Token::LessEqual    => BinaryOp::LessEqual,
+            Token::Greater      => BinaryOp::Greater,
+            Token::GreaterEqual => BinaryOp::GreaterEqual,
+            Token::And          => BinaryOp::And,
+            Token::Or           => BinaryOp::Or,
+            _                   => return None,
+        };
+
+        Some(op)
+    }
+
+    /// How tightly the operator holds on to its operands: (left, right). Right is higher for
+    /// left-associative operators, so `1 - 2 - 3` is `(1 - 2) - 3`.
+    fn binding_power(&self) -> (u8, u8) {
+        match self {
+            BinaryOp::Or  => (1, 2),
+            BinaryOp::And => (3, 4),
+            BinaryOp::Equal | BinaryOp::NotEqual | BinaryOp::Less | BinaryOp::LessEqual | BinaryOp::Greater | BinaryOp::GreaterEqual => (5, 6),
+            BinaryOp::Add | BinaryOp::Subtract => (7, 8),
+            BinaryOp::Multiply | BinaryOp::Divide | BinaryOp::Remainder => (9, 10),
+        }
+    }
+}
+
+/// Binds tighter than any binary operator, so `-a * b` is `(-a) * b`
+const UNARY_POWER: u8 = 11;
+
+/// Something that has a value
+#[derive(Debug, Clone, PartialEq)]
+pub enum Expr {
+    Int(i64),
+    Bool(bool),
+    Var(String),
+    Unary { op: UnaryOp, operand: Box<Expr> },
+    Binary { op: BinaryOp, lhs: Box<Expr>, rhs: Box<Expr> },
+    Block(Block),
+    /// `else` is another `if` or a block, or missing (which makes the whole thing `()`)
+    If { condition: Box<Expr>, then: Block, otherwise: Option<Box<Expr>> },
+    Call { function: String, args: Vec<Expr> },
+}
+
+impl Expr {
+    /// Blocks and `if`s don't need a `;` to be used as statements, just like in Rust
+    fn is_block_like(&self) -> bool {
+        matches!(self, Expr::Block(_) | Expr::If { .. })
+    }
+}
+
+/// Something that does something, with no value of its own
+#[derive(Debug, Clone, PartialEq)]
+pub enum Stmt {
+    Let { name: String, value: Expr },
+    Fn(Rc<Function>),
+    Expr(Expr),
+}
+
+/// A function definition
+#[derive(Debug, Clone, PartialEq)]
+pub struct Function {
+    pub name: String,
+    pub params: Vec<String>,
+    pub body: Block,
+}
+
+/// Statements, then optionally an expression without a `;` after it: the block's value
+#[derive(Debug, Clone, PartialEq, Default)]
+pub struct Block {
+    pub stmts: Vec<Stmt>,
+    pub tail: Option<Box<Expr>>,
+}
+
+/// Deepest expressions (and function definitions) can nest before they're an error, instead of
+/// overflowing the real stack. Every operator in a chain like `1 + 2 + 3` puts the ones before it
+/// a level deeper in the syntax tree, so long chains count too.
+pub const MAX_NESTING: usize = 128;
+
+/// A Pratt parser over a list of tokens
+pub struct Parser {
+    tokens: Vec<Spanned>,
+    position: usize,
+    /// Where the source ends, for errors about running out of tokens
+    end: usize,
+    /// How deep in the syntax tree the parser is
+    depth: usize,
+}
+
+impl Parser {
+    pub fn new(tokens: Vec<Spanned>, source_len: usize) -> Self {
+        Parser { tokens, position: 0, end: source_len, depth: 0 }
+    }
+
+    fn peek(&self) -> Option<&Token> {
+        self.tokens.get(self.position).map(|spanned| &spanned.token)
+    }
+
+    fn offset(&self) -> usize {
+        self.tokens.get(self.position).map_or(self.end, |spanned| spanned.offset)
+    }
+
+    fn next(&mut self) -> Option<Token> {
+        let token = self.peek().cloned();
+        self.position += 1;
+        token
+    }
+
+    fn eat(&mut self, token: &Token) -> bool {
+        let matches = self.peek() == Some(token);
+
+        if matches {
+            self.position += 1;
+        }
+
+        matches
+    }
+
+    fn error(&self, message: String) -> ParseError {
+        match self.peek() {
+            Some(_) => ParseError::new(message, self.offset()),
+            None    => ParseError::incomplete(message, self.end),
+        }
+    }
+
+    fn expect(&mut self, token: &Token) -> Result<(), ParseError> {
+        if self.eat(token) {
+            return Ok(());
+        }
+
+        let found = self.peek().map_or(String::from("the end"), |found| format!("'{found}'"));
+
+        Err(self.error(format!("expected '{token}', found {found}")))
+    }
+
+    // Go a level deeper, unless that's too deep already
+    fn descend(&mut self) -> Result<(), ParseError> {
+        if self.depth >= MAX_NESTING {
+            return Err(ParseError::new(format!("nested more than {MAX_NESTING} deep"), self.offset()));
+        }
+
+        self.depth += 1;
+        Ok(())
+    }
+
+    // Parse something inside whatever's being parsed now, and come back up afterwards
+    fn nested<T>(&mut self, parse: impl FnOnce(&mut Self) -> Result<T, ParseError>) -> Result<T, ParseError> {
+        let depth = self.depth;
+
+        self.descend()?;
+        let result = parse(self);
+        self.depth = depth;
+
+        result
+    }
+
+    fn ident(&mut self) -> Result<String, ParseError> {
+        match self.peek() {
+            Some(Token::Ident(name)) => {
+                let name = name.clone();
+                self.position += 1;
+                Ok(name)
+            }
+            Some(found) => Err(self.error(format!("expected a name, found '{found}'"))),
+            None        => Err(self.error(String::from("expected a name, found the end"))),
+        }
+    }
+
+    /// A whole program: the inside of a block, without the braces
+    pub fn program(&mut self) -> Result<Block, ParseError> {
+        let block = self.block_contents()?;
+
+        match self.peek() {
+            Some(token) => Err(self.error(format!("unexpected '{token}'"))),
+            None        => Ok(block),
+        }
+    }
+
+    // Statements up to a `}` or the end of the tokens
+    fn block_contents(&mut self) -> Result<Block, ParseError> {
+        let mut block = Block::default();
+
+        while !matches!(self.peek(), None | Some(Token::RightBrace)) {
+            if self.eat(&Token::Semicolon) {
+                continue;
+            }
+
+            if self.eat(&Token::Let) {
+                let name = self.ident()?;
+                self.expect(&Token::Assign)?;
+                let value = self.expr(0)?;
+                self.expect(&Token::Semicolon)?;
+
+                block.stmts.push(Stmt::Let { name, value });
+                continue;
+            }
+
+            if self.eat(&Token::Fn) {
+                block.stmts.push(Stmt::Fn(Rc::new(self.function()?)));
+                continue;
+            }
+
+            let expr = self.expr(0)?;
+
+            // An expression right before the closing brace (or the end) is the block's value
+            if matches!(self.peek(), None | Some(Token::RightBrace)) {
+                block.tail = Some(Box::new(expr));
+                break;
+            }
+
+            if !expr.is_block_like() {
+                self.expect(&Token::Semicolon)?;
+            }
+
+            block.stmts.push(Stmt::Expr(expr));
+        }
+
+        Ok(block)
+    }
+
+    fn block(&mut self) -> Result<Block, ParseError> {
+        self.expect(&Token::LeftBrace)?;
+        let block = self.block_contents()?;
+        self.expect(&Token::RightBrace)?;
+
+        Ok(block)
+    }
+
+    // After the `fn`: name(params) { body }
+    fn function(&mut self) -> Result<Function, ParseError> {
+        let name = self.ident()?;
+        let mut params = Vec::new();
+
+        self.expect(&Token::LeftParen)?;
+
+        while !self.eat(&Token::RightParen) {
+            params.push(self.ident()?);
+
+            if !self.eat(&Token::Comma) {
+                self.expect(&Token::RightParen)?;
+                break;
+            }
+        }
+
+        let body = self.nested(Parser::block)?;
+
+        Ok(Function { name, params, body })
+    }
+
+    /// An expression whose operators all bind tighter than `min_power`
+    fn expr(&mut self, min_power: u8) -> Result<Expr, ParseError> {
+        self.nested(|parser| parser.operators(min_power))
+    }
+
+    // `expr`, inside the level `nested` counted for it
+    fn operators(&mut self, min_power: u8) -> Result<Expr, ParseError> {
+        let mut lhs = self.prefix()?;
+
+        while let Some(op) = self.peek().and_then(BinaryOp::from_token) {
+            let (left_power, right_power) = op.binding_power();
+
+            if left_power < min_power {
+                break;
+            }
+
+            // `lhs` ends up a level further down, under this operator (`nested` comes back up)
+            self.descend()?;
+            self.position += 1;
+
+            let rhs = self.expr(right_power)?;
+            lhs = Expr::Binary { op, lhs: Box::new(lhs), rhs: Box::new(rhs) };
+        }
+
+        Ok(lhs)
+    }
+
+    // Everything that can start an expression
+    fn prefix(&mut self) -> Result<Expr, ParseError> {
+        let offset = self.offset();
+
+        let Some(token) = self.next() else {
+            return Err(ParseError::incomplete(String::from("expected an expression, found the end"), self.end));
+        };
+
+        let expr = match token {
+            Token::Int(n)      => Expr::Int(n),
+            Token::True        => Expr::Bool(true),
+            Token::False       => Expr::Bool(false),
+            Token::Minus       => Expr::Unary { op: UnaryOp::Negate, operand: Box::new(self.expr(UNARY_POWER)?) },
+            Token::Bang        => Expr::Unary { op: UnaryOp::Not, operand: Box::new(self.expr(UNARY_POWER)?) },
+            Token::LeftParen   => {
+                let expr = self.expr(0)?;
+                self.expect(&Token::RightParen)?;
+                expr
+            }
+            Token::LeftBrace   => {
+                self.position -= 1;
+                Expr::Block(self.block()?)
+            }
+            Token::If          => self.if_expr()?,
+            Token::Ident(name) => {
+                if !self.eat(&Token::LeftParen) {
+                    return Ok(Expr::Var(name));
+                }
+
+                let mut args = Vec::new();
+
+                while !self.eat(&Token::RightParen) {
+                    args.push(self.expr(0)?);
+
+                    if !self.eat(&Token::Comma) {
+                        self.expect(&Token::RightParen)?;
+                        break;
+                    }
+                }
+
+                Expr::Call { function: name, args }
+            }
+            // `let` is a statement, so it can't be used as a value (see the chapter's `let x = (let z = 6);`)
+            Token::Let => return Err(ParseError::new(String::from("`let` is a statement, not an expression"), offset)),
+            token      => return Err(ParseError::new(format!("expected an expression, found '{token}'"), offset)),
+        };
+
+        Ok(expr)
+    }
+
+    // After the `if`: condition { then } else ...
+    fn if_expr(&mut self) -> Result<Expr, ParseError> {
+        let condition = Box::new(self.expr(0)?);
+        let then = self.block()?;
+
+        let otherwise = match self.eat(&Token::Else) {
+            true if self.eat(&Token::If) => Some(Box::new(self.nested(Parser::if_expr)?)),
+            true                         => Some(Box::new(Expr::Block(self.block()?))),
+            false                        => None,
+        };
+
+        Ok(Expr::If { condition, then, otherwise })
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::expr::parse;
+
+    fn int(n: i64) -> Box<Expr> {
+        Box::new(Expr::Int(n))
+    }
+
+    // The program's value, which is all most of these are about
+    fn tail(source: &str) -> Expr {
+        *parse(source).unwrap().tail.unwrap()
+    }
+
+    #[test]
+    fn operators_bind_by_precedence_and_to_the_left() {
+        assert_eq!(tail("1 - 2 - 3"), Expr::Binary {
+            op: BinaryOp::Subtract,
+            lhs: Box::new(Expr::Binary { op: BinaryOp::Subtract, lhs: int(1), rhs: int(2) }),
+            rhs: int(3),
+        });
+        assert_eq!(tail("-1 * 2"), Expr::Binary {
+            op: BinaryOp::Multiply,
+            lhs: Box::new(Expr::Unary { op: UnaryOp::Negate, operand: int(1) }),
+            rhs: int(2),
+        });
+    }
+
+    #[test]
+    fn a_block_without_a_semicolon_at_the_end_has_a_value() {
+        let program = parse("let b = { let a = 5; a + 2 }; b").unwrap();
+
+        assert_eq!(program.stmts.len(), 1);
+        assert_eq!(program.tail, Some(Box::new(Expr::Var(String::from("b")))));
+        assert_eq!(parse("{ 1; }").unwrap().tail, Some(Box::new(Expr::Block(Block {
+            stmts: vec![Stmt::Expr(Expr::Int(1))],
+            tail: None,
+        }))));
+    }
+
+    #[test]
+    fn let_is_not_an_expression() {
+        let err = parse("let x = (let z = 6);").unwrap_err();
+
+        assert_eq!(err.message, "`let` is a statement, not an expression");
+        assert_eq!(err.offset, 9);
+        assert!(!err.incomplete);
+    }
+
+    #[test]
+    fn running_out_of_input_is_incomplete() {
+        for source in ["{ 1 +", "fn f(n) {", "if true { 1 } else", "(1"] {
+            assert!(parse(source).unwrap_err().incomplete, "{source}");
+        }
+
+        assert!(!parse("1 )").unwrap_err().incomplete);
+    }
+
+    #[test]
+    fn nesting_up_to_the_limit_parses() {
+        // The program's value is one expression deep already
+        let depth = MAX_NESTING - 1;
+        let source = format!("{}1{}", "(".repeat(depth), ")".repeat(depth));
+
+        assert_eq!(tail(&source), Expr::Int(1));
+    }
+
+    #[test]
+    fn nesting_too_deep_is_an_error_instead_of_a_stack_overflow() {
+        let sources = [
+            format!("{}1{}", "(".repeat(3000), ")".repeat(3000)),
+            format!("{}1{}", "{".repeat(3000), "}".repeat(3000)),
+            format!("{}1", "-".repeat(3000)),
+            format!("{}1", "!".repeat(3000)),
+            format!("{}{}", "fn f() {".repeat(3000), "}".repeat(3000)),
+            format!("if true {{ 1 }}{} else {{ 0 }}", " else if true { 1 }".repeat(3000)),
+            // Unfinished, but more input won't help
+            "(".repeat(3000),
+        ];
+
+        for source in &sources {
+            let err = parse(source).unwrap_err();
+
+            assert_eq!(err.message, format!("nested more than {MAX_NESTING} deep"));
+            assert!(!err.incomplete);
+        }
+    }
+
+    #[test]
+    fn chains_of_operators_count_as_nesting() {
+        let chain = |terms: usize| vec!["1"; terms].join(" + ");
+
+        // The first `+` ends up deepest, with the program's value and every other `+` above it
+        assert!(parse(&chain(MAX_NESTING - 1)).is_ok());
+
+        // Flat, but too deep a tree to evaluate (or even drop) safely
+        for terms in [MAX_NESTING, 1001, 300_000] {
+            let err = parse(&chain(terms)).unwrap_err();
+
+            assert_eq!(err.message, format!("nested more than {MAX_NESTING} deep"), "{terms} terms");
+            assert!(!err.incomplete);
+        }
+
+        // Nesting inside a chain doesn't reset the count
+        assert!(parse(&format!("{} + ({})", chain(64), chain(64))).is_err());
+        assert!(parse(&format!("({}) + {}", chain(64), chain(32))).is_ok());
+    }
+}
@@ -0,0 +1,225 @@
+use num_bigint::BigUint;
+
+/* Fibonacci numbers
+
+F(0) = 0, F(1) = 1, and every number after that is the sum of the two before it. They grow fast:
+F(47) is already too big for an `i32`, F(94) for a `u64` and F(187) for a `u128`. So every
+fixed-size variant here returns `None` instead of overflowing, and `big` goes as far as memory
+allows.
+
+*/
+
+/// Largest `n` whose Fibonacci number fits in a `u64`
+pub const MAX_U64: u32 = 93;
+
+/// Largest `n` whose Fibonacci number fits in a `u128`
+pub const MAX_U128: u32 = 186;
+
+/// Walk up the sequence keeping only the last two numbers. O(n).
+pub fn iterative(n: u32) -> Option<u64> {
+    if n > MAX_U64 {
+        return None;
+    }
+
+    let (mut a, mut b) = (0u64, 1u64);
+
+    // `b` runs one ahead of `a`, so for n = 93 it wraps on the last step. It's never read after that.
+    for _ in 0..n {
+        (a, b) = (b, a.wrapping_add(b));
+    }
+
+    Some(a)
+}
+
+/// The recursive definition, but each number is only ever worked out once
+#[derive(Debug, Clone)]
+pub struct Memo {
+    cache: Vec<u64>,
+}
+
+impl Default for Memo {
+    fn default() -> Self {
+        Memo::new()
+    }
+}
+
+impl Memo {
+    pub fn new() -> Self {
+        Memo { cache: vec![0, 1] }
+    }
+
+    pub fn get(&mut self, n: u32) -> Option<u64> {
+        if n > MAX_U64 {
+            return None;
+        }
+
+        if let Some(&fib) = self.cache.get(n as usize) {
+            return Some(fib);
+        }
+
+        let fib = self.get(n - 1)? + self.get(n - 2)?;
+        self.cache.push(fib);
+
+        Some(fib)
+    }
+}
+
+/// `Memo::get` with a fresh memo
+pub fn memoized(n: u32) -> Option<u64> {
+    Memo::new().get(n)
+}
+
+/// Fast doubling: F(2k) = F(k)(2F(k+1) - F(k)) and F(2k+1) = F(k)² + F(k+1)², halving `n` at
+/// every step. O(log n).
+pub fn fast_doubling(n: u32) -> Option<u128> {
+    // Returns (F(n), F(n + 1)), which is one step further than the caller asked for
+    fn pair(n: u32) -> Option<(u128, u128)> {
+        if n == 0 {
+            return Some((0, 1));
+        }
+
+        let (a, b) = pair(n / 2)?;
+        let even = a.checked_mul(b.checked_mul(2)?.checked_sub(a)?)?;
+        let odd = a.checked_mul(a)?.checked_add(b.checked_mul(b)?)?;
+
+        if n.is_multiple_of(2) {
+            Some((even, odd))
+        } else {
+            Some((odd, even.checked_add(odd)?))
+        }
+    }
+
+    // F(n + 1) overflows one step early, so check `n` instead of trusting `pair`
+    if n > MAX_U128 {
+        return None;
+    }
+
+    if n == MAX_U128 {
+        return Some(fast_doubling(n - 1)? + fast_doubling(n - 2)?);
+    }
+
+    pair(n).map(|(fib, _)| fib)
+}
+
+/// Fast doubling without a size limit
+pub fn big(n: u64) -> BigUint {
+    fn pair(n: u64) -> (BigUint, BigUint) {
+        if n == 0 {
+            return (BigUint::ZERO, BigUint::from(1u32));
+        }
+
+        let (a, b) = pair(n / 2);
+        let even = &a * (&b * 2u32 - &a);
+        let odd = &a * &a + &b * &b;
+
+        if n.is_multiple_of(2) {
+            (even, odd)
+        } else {
+            let next = &even + &odd;
+            (odd, next)
+        }
+    }
+
+    pair(n).0
+}
+
+/// Every Fibonacci number that fits in a `u64`, starting from F(0)
+#[derive(Debug, Clone)]
+pub struct Fibonacci {
+    next: Option<u64>,
+    after: Option<u64>,
+}
+
+impl Default for Fibonacci {
+    fn default() -> Self {
+        Fibonacci::new()
+    }
+}
+
+impl Fibonacci {
+    pub fn new() -> Self {
+        Fibonacci { next: Some(0), after: Some(1) }
+    }
+}
+
+impl Iterator for Fibonacci {
+    type Item = u64;
+
+    fn next(&mut self) -> Option<u64> {
+        let current = self.next?;
+        let sum = self.after.and_then(|after| current.checked_add(after));
+
+        (self.next, self.after) = (self.after, sum);
+
+        Some(current)
+    }
+}
+
+impl std::iter::FusedIterator for Fibonacci {}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn starts_from_zero_and_one() {
+        let first: Vec<u64> = Fibonacci::new().take(10).collect();
+
+        assert_eq!(first, [0, 1, 1, 2, 3, 5, 8, 13, 21, 34]);
+    }
+
+    #[test]
+    fn all_variants_agree_up_to_max_u64() {
+        let sequence: Vec<u64> = Fibonacci::new().collect();
+        let mut memo = Memo::new();
+
+        assert_eq!(sequence.len(), MAX_U64 as usize + 1);
+
+        for n in 0..=MAX_U64 {
+            let expected = sequence[n as usize];
+
+            assert_eq!(iterative(n), Some(expected), "iterative({n})");
+            assert_eq!(memoized(n), Some(expected), "memoized({n})");
+            assert_eq!(memo.get(n), Some(expected), "Memo::get({n})");
+            assert_eq!(fast_doubling(n), Some(u128::from(expected)), "fast_doubling({n})");
+            assert_eq!(big(u64::from(n)), BigUint::from(expected), "big({n})");
+        }
+    }
+
+    #[test]
+    fn fast_doubling_and_big_agree_up_to_max_u128() {
+        // Checked against the definition, rather than against each other
+        let (mut a, mut b) = (BigUint::ZERO, BigUint::from(1u32));
+
+        for n in 0..=MAX_U128 {
+            assert_eq!(fast_doubling(n).map(BigUint::from), Some(a.clone()), "fast_doubling({n})");
+            assert_eq!(big(u64::from(n)), a, "big({n})");
+
+            (a, b) = (b.clone(), a + b);
+        }
+    }
+
+    #[test]
+    fn u64_variants_stop_past_max_u64() {
+        assert_eq!(iterative(94), None);
+        assert_eq!(memoized(94), None);
+        assert_eq!(Memo::new().get(94), None);
+        assert_eq!(Fibonacci::new().nth(94), None);
+        assert!(BigUint::from(u64::MAX) < big(94));
+    }
+
+    #[test]
+    fn fast_doubling_stops_past_max_u128() {
+        assert_eq!(fast_doubling(187), None);
+        assert_eq!(fast_doubling(u32::MAX), None);
+        assert!(BigUint::from(u128::MAX) < big(187));
+    }
+
+    #[test]
+    fn iterator_is_fused() {
+        let mut fib = Fibonacci::new().skip(MAX_U64 as usize + 1);
+
+        assert_eq!(fib.next(), None);
+        assert_eq!(fib.next(), None);
+    }
+}
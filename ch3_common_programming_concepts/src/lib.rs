@@ -0,0 +1,23 @@
+/* Common Programming Concepts
+
+The bits of chapter 3 that grew past a quick function in `main.rs` live in this library, so they
+can be reused (and `main.rs` stays a tour of the chapter).
+
+*/
+
+pub mod expr;
+pub mod fib;
+pub mod number_words;
+pub mod overflow;
+pub mod retry;
+pub mod sign;
+pub mod songs;
+pub mod twelve_days;
+pub mod units;
+
+pub use fib::Fibonacci;
+pub use retry::{Backoff, Retry};
+pub use sign::{HasSign, Sign};
+pub use songs::Song;
+pub use twelve_days::TwelveDays;
+pub use units::{Celsius, Fahrenheit, Kelvin, Quantity};
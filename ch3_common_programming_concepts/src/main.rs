@@ -1,4 +1,16 @@
+use std::env;
+use std::process;
+use std::time::Duration;
+
+use ch3_common_programming_concepts::expr;
+use ch3_common_programming_concepts::fib::{self, Fibonacci};
+use ch3_common_programming_concepts::number_words::{cardinal, ordinal};
+use ch3_common_programming_concepts::retry::{Backoff, ManualClock, Retry};
+use ch3_common_programming_concepts::sign::{sign, HasSign};
+use ch3_common_programming_concepts::{Celsius, Fahrenheit, TwelveDays};
+
 fn main() {
+    #[allow(unused_mut, unused_variables)]
     let mut y = 5; // Mutable variable, as Rust has all variables immutable by default
     let x = 5;
     println!("The value of x is: {}", x);
@@ -23,16 +35,37 @@ fn main() {
 
     println!("What is b? {b}");
 
+    // The same thing, run by the little interpreter in src/expr.rs (try `cargo run --bin repl`)
+    match expr::eval("let b = { let a = 5; a + 2 }; b") {
+        Ok(value) => println!("What is b in the interpreter? {value}"),
+        Err(err)  => println!("{err}"),
+    }
+
     let five = five();
 
     println!("five is {five}");
 
-    // Function with parameters, control statements, and the return of a boolean value
-    let greater_than = positive(five);
-    let negative_one = positive(-1);
+    // Which side of zero? Zero is neither positive nor negative, and NaN has no sign (see src/sign.rs)
+    let greater_than = five.is_positive();
+    let negative_one = (-1).is_positive();
 
     println!("Is {five} a positive number? {greater_than}");
     println!("Is -1 a positive number? {negative_one}");
+    println!("Is 0 a positive number? {}", 0.is_positive());
+
+    for x in [-2.5, -0.0, 0.0, f64::INFINITY, f64::NAN] {
+        match sign(x) {
+            Some(sign) => println!("{x} is {sign}"),
+            None       => println!("{x} has no sign"),
+        }
+    }
+
+    // `five` is an i32, and `i32::MAX + five` would panic in a debug build. The methods that
+    // decide what overflow means instead (see src/overflow.rs, or `cargo run --bin overflow`):
+    println!("i32::MAX.wrapping_add({five}) = {}", i32::MAX.wrapping_add(five));
+    println!("i32::MAX.checked_add({five}) = {:?}", i32::MAX.checked_add(five));
+    println!("i32::MAX.saturating_add({five}) = {}", i32::MAX.saturating_add(five));
+    println!("i32::MAX.overflowing_add({five}) = {:?}", i32::MAX.overflowing_add(five));
 
     // loop-de-loop
     let mut count = 0;
@@ -59,16 +92,43 @@ fn main() {
 
     println!("\nEnd count = {count}");
 
-    // recursive fibonacci implementation in Rust
+    // The same loop-until-done idea as a reusable retry, here with something that fails twice (see src/retry.rs)
+
+    let clock = ManualClock::new();
+    let result = Retry::new(5)
+        .backoff(Backoff::exponential(Duration::from_millis(100), Duration::from_secs(1)))
+        .clock(&clock)
+        .run(|attempt| if attempt < 3 { Err(format!("attempt {attempt} failed")) } else { Ok(attempt) });
+
+    println!("Retry result: {result:?} after waiting {:?}", clock.sleeps());
+
+    // The chapter's exercise: converting temperatures between Fahrenheit and Celsius (see src/units.rs)
+
+    let body_temperature = Fahrenheit::new(98.6).expect("warmer than absolute zero");
+    println!("{body_temperature} is {}", Celsius::from(body_temperature));
+
+    // fibonacci, without the exponential recursion or the overflow (see src/fib.rs)
 
     let num = 6;
-    let fib = fibonacci(num);
+    let fib = fib::iterative(num).expect("fits in a u64");
 
     println!("fibonacci sequence of {num} = {fib}");
+    println!("(or, in words, the {} fibonacci number is {})", ordinal(num), cardinal(fib));
 
-    // self-made Twelve Days of Christmas jingle (half-baked atm)
+    let first_ten: Vec<u64> = Fibonacci::new().take(10).collect();
+    println!("The first ten: {first_ten:?}");
+
+    match fib::memoized(fib::MAX_U64 + 1) {
+        Some(fib) => println!("fibonacci of {} = {fib}", fib::MAX_U64 + 1),
+        None      => println!("fibonacci of {} doesn't fit in a u64", fib::MAX_U64 + 1),
+    }
 
-    twelve_days_of_christmas();
+    println!("fibonacci of {} = {:?}", fib::MAX_U128, fib::fast_doubling(fib::MAX_U128));
+    println!("fibonacci of 500 = {}", fib::big(500));
+
+    // Twelve Days of Christmas, the whole song (or another one, given a file of gifts to sing)
+
+    twelve_days_of_christmas(env::args().nth(1));
 
 }
 
@@ -86,55 +146,14 @@ fn five() -> i32 {
     5
 }
 
-// Can return a value at the very end, or even at the beginning to prevent unintended consequences in functions, when any sort of processing is done
-fn positive(x: i32) -> bool {
-    if x < 0 {
-        return false;
-    }
-
-    true
-}
-
-fn fibonacci(n: i32) -> i32 {
-
-    if n < 0 {
-        return 0;
-    }
-
-    if n == 0 {
-        return 0;
-    }
-
-    if n == 1 || n == 2 {
-        return 1;
-    }
+fn twelve_days_of_christmas(gift_file: Option<String>) {
+    let song = match gift_file {
+        Some(path) => TwelveDays::load(&path).unwrap_or_else(|err| {
+            eprintln!("{path}: {err}");
+            process::exit(1);
+        }),
+        None => TwelveDays::default(),
+    };
 
-    fibonacci(n - 1) + fibonacci(n - 2)
+    println!("\n{}", song.lyrics());
 }
-
-fn twelve_days_of_christmas() {
-
-    // let days = 12;
-    let mut current_day = 1;
-
-    for days in 1..12 {
-        println!("On day {current_day} of Christmas, my true love gave to me...");
-
-        let mut day = current_day;
-        for current in 1..current_day {
-            println!("{day} items of blank");
-            day -= 1;
-        }
-        
-        
-        if current_day == 1 {
-            println!("A partridge in a pear tree!");
-        } else {
-            println!("And a partridge in a pear tree!");
-        }
-
-        current_day += 1;
-
-    }
-
-}
\ No newline at end of file
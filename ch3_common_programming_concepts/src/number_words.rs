@@ -0,0 +1,464 @@
+use std::error::Error;
+use std::fmt;
+
+/* Number words
+
+Numbers as English words and back again: 123 is "one hundred twenty-three" as a cardinal and
+"one hundred twenty-third" as an ordinal. Any integer type works, all the way up to `u128::MAX`
+(three hundred forty undecillion ...). Negative numbers start with "negative".
+
+Parsing goes the other way, and also accepts "minus", "and" between words ("one hundred and
+five") and an ordinal last word.
+
+*/
+
+const ONES: [&str; 20] = [
+    "zero", "one", "two", "three", "four", "five", "six", "seven", "eight", "nine", "ten", "eleven",
+    "twelve", "thirteen", "fourteen", "fifteen", "sixteen", "seventeen", "eighteen", "nineteen",
+];
+
+const TENS: [&str; 10] = ["", "", "twenty", "thirty", "forty", "fifty", "sixty", "seventy", "eighty", "ninety"];
+
+/// Powers of a thousand, from 10³ up to 10³⁶ (`u128::MAX` is about 3.4 × 10³⁸)
+const SCALES: [&str; 12] = [
+    "thousand", "million", "billion", "trillion", "quadrillion", "quintillion", "sextillion",
+    "septillion", "octillion", "nonillion", "decillion", "undecillion",
+];
+
+/// Ordinals that aren't just the cardinal with "th" on the end
+const IRREGULAR_ORDINALS: [(&str, &str); 7] = [
+    ("one", "first"),
+    ("two", "second"),
+    ("three", "third"),
+    ("five", "fifth"),
+    ("eight", "eighth"),
+    ("nine", "ninth"),
+    ("twelve", "twelfth"),
+];
+
+/// Integer types that can be written out in words
+pub trait Integer: Copy {
+    /// Whether it's below zero, and how far from zero it is
+    fn sign_and_magnitude(self) -> (bool, u128);
+}
+
+macro_rules! impl_signed {
+    ($($t:ty)*) => {$(
+        impl Integer for $t {
+            fn sign_and_magnitude(self) -> (bool, u128) {
+                (self < 0, self.unsigned_abs() as u128)
+            }
+        }
+    )*};
+}
+
+macro_rules! impl_unsigned {
+    ($($t:ty)*) => {$(
+        impl Integer for $t {
+            fn sign_and_magnitude(self) -> (bool, u128) {
+                (false, self as u128)
+            }
+        }
+    )*};
+}
+
+impl_signed!(i8 i16 i32 i64 i128 isize);
+impl_unsigned!(u8 u16 u32 u64 u128 usize);
+
+// 0 to 999, without "zero"
+fn hundreds(n: u128, words: &mut Vec<String>) {
+    let (hundred, rest) = (n / 100, n % 100);
+
+    if hundred > 0 {
+        words.push(ONES[hundred as usize].to_string());
+        words.push(String::from("hundred"));
+    }
+
+    match rest {
+        0      => {}
+        1..=19 => words.push(ONES[rest as usize].to_string()),
+        _      => match rest % 10 {
+            0    => words.push(TENS[(rest / 10) as usize].to_string()),
+            ones => words.push(format!("{}-{}", TENS[(rest / 10) as usize], ONES[ones as usize])),
+        },
+    }
+}
+
+/// `n` in words, e.g. "one hundred twenty-three"
+pub fn cardinal(n: impl Integer) -> String {
+    let (negative, mut magnitude) = n.sign_and_magnitude();
+
+    if magnitude == 0 {
+        return String::from("zero");
+    }
+
+    // Split into groups of three digits, lowest first
+    let mut groups = Vec::new();
+
+    while magnitude > 0 {
+        groups.push(magnitude % 1000);
+        magnitude /= 1000;
+    }
+
+    let mut words = Vec::new();
+
+    if negative {
+        words.push(String::from("negative"));
+    }
+
+    for (scale, &group) in groups.iter().enumerate().rev() {
+        if group == 0 {
+            continue;
+        }
+
+        hundreds(group, &mut words);
+
+        if scale > 0 {
+            words.push(SCALES[scale - 1].to_string());
+        }
+    }
+
+    words.join(" ")
+}
+
+// The ordinal form of a single cardinal word
+fn ordinal_word(word: &str) -> String {
+    if let Some((_, ordinal)) = IRREGULAR_ORDINALS.iter().find(|(cardinal, _)| *cardinal == word) {
+        return ordinal.to_string();
+    }
+
+    match word.strip_suffix('y') {
+        Some(stem) => format!("{stem}ieth"),
+        None       => format!("{word}th"),
+    }
+}
+
+/// `n` in words as an ordinal, e.g. "one hundred twenty-third"
+pub fn ordinal(n: impl Integer) -> String {
+    let words = cardinal(n);
+
+    // Only the last word changes, and in "twenty-three" that's the "three"
+    let split = words.rfind([' ', '-']).map_or(0, |i| i + 1);
+    let (head, last) = words.split_at(split);
+
+    format!("{head}{}", ordinal_word(last))
+}
+
+/// "st", "nd", "rd" or "th", to go after `n` written in digits
+pub fn ordinal_suffix(n: impl Integer) -> &'static str {
+    let (_, magnitude) = n.sign_and_magnitude();
+
+    match (magnitude % 10, magnitude % 100) {
+        (_, 11..=13) => "th",
+        (1, _)       => "st",
+        (2, _)       => "nd",
+        (3, _)       => "rd",
+        _            => "th",
+    }
+}
+
+/// Why some words aren't a number
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum ParseWordsError {
+    /// Nothing but whitespace
+    Empty,
+    /// A word that isn't part of any number
+    UnknownWord(String),
+    /// A number word where it can't go, like the "two" in "one two"
+    Misplaced(String),
+    /// More than `u128::MAX`
+    Overflow,
+}
+
+impl fmt::Display for ParseWordsError {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        match self {
+            ParseWordsError::Empty             => write!(f, "no words to read a number from"),
+            ParseWordsError::UnknownWord(word) => write!(f, "\"{word}\" isn't a number word"),
+            ParseWordsError::Misplaced(word)   => write!(f, "\"{word}\" can't go there"),
+            ParseWordsError::Overflow          => write!(f, "too big, the biggest number is {}", u128::MAX),
+        }
+    }
+}
+
+impl Error for ParseWordsError {}
+
+/// A number read from words
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct Number {
+    pub negative: bool,
+    pub magnitude: u128,
+    /// Whether the last word was an ordinal ("third" rather than "three")
+    pub ordinal: bool,
+}
+
+impl Number {
+    /// The number, if it fits in an `i128`
+    pub fn to_i128(&self) -> Option<i128> {
+        if self.negative {
+            0i128.checked_sub_unsigned(self.magnitude)
+        } else {
+            i128::try_from(self.magnitude).ok()
+        }
+    }
+
+    /// The number, if it isn't negative
+    pub fn to_u128(&self) -> Option<u128> {
+        (!self.negative || self.magnitude == 0).then_some(self.magnitude)
+    }
+}
+
+impl fmt::Display for Number {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        if self.negative {
+            write!(f, "-")?;
+        }
+
+        write!(f, "{}", self.magnitude)?;
+
+        if self.ordinal {
+            write!(f, "{}", ordinal_suffix(self.magnitude))?;
+        }
+
+        Ok(())
+    }
+}
+
+/// What a single word means
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+enum Token {
+    /// 0 to 19
+    Ones(u128),
+    /// 20, 30, ... 90
+    Tens(u128),
+    Hundred,
+    /// Index into `SCALES`
+    Scale(usize),
+}
+
+fn token(word: &str) -> Option<Token> {
+    if let Some(n) = ONES.iter().position(|&w| w == word) {
+        return Some(Token::Ones(n as u128));
+    }
+
+    if let Some(n) = TENS.iter().position(|&w| !w.is_empty() && w == word) {
+        return Some(Token::Tens(n as u128 * 10));
+    }
+
+    if word == "hundred" {
+        return Some(Token::Hundred);
+    }
+
+    SCALES.iter().position(|&w| w == word).map(Token::Scale)
+}
+
+// The cardinal word an ordinal one comes from, if it is one. Going through `ordinal_word` means
+// only the real spelling counts, so "nineth" and "twentyth" aren't ordinals.
+fn cardinal_word(word: &str) -> Option<&'static str> {
+    let cardinals = ONES.iter().chain(TENS.iter().filter(|tens| !tens.is_empty())).chain(&["hundred"]).chain(&SCALES);
+
+    cardinals.copied().find(|cardinal| ordinal_word(cardinal) == word)
+}
+
+/// Read a number written in words, cardinal or ordinal
+pub fn parse(words: &str) -> Result<Number, ParseWordsError> {
+    let lowercase = words.to_lowercase();
+    let mut words: Vec<&str> = lowercase
+        .split(|c: char| c.is_whitespace() || c == '-' || c == ',')
+        .filter(|word| !word.is_empty() && *word != "and")
+        .collect();
+
+    let negative = matches!(words.first(), Some(&"negative" | &"minus"));
+
+    if negative {
+        words.remove(0);
+    }
+
+    let Some(&last) = words.last() else {
+        return Err(ParseWordsError::Empty);
+    };
+
+    // Only the last word can be an ordinal
+    let last_cardinal = match token(last) {
+        Some(_) => None,
+        None    => cardinal_word(last),
+    };
+    let ordinal = last_cardinal.is_some();
+
+    if let Some(cardinal) = last_cardinal {
+        *words.last_mut().unwrap() = cardinal;
+    }
+
+    let (mut total, mut group) = (0u128, 0u128);
+    let mut previous: Option<Token> = None;
+    let mut last_scale: Option<usize> = None;
+
+    for &word in &words {
+        let token = token(word).ok_or_else(|| ParseWordsError::UnknownWord(word.to_string()))?;
+        let misplaced = || ParseWordsError::Misplaced(word.to_string());
+
+        match token {
+            // "zero" only ever goes alone
+            Token::Ones(0) if words.len() > 1 => return Err(misplaced()),
+            Token::Ones(n) => {
+                let fits = match previous {
+                    None | Some(Token::Hundred | Token::Scale(_)) => true,
+                    Some(Token::Tens(_))                          => n < 10,
+                    Some(Token::Ones(_))                          => false,
+                };
+
+                if !fits {
+                    return Err(misplaced());
+                }
+
+                group += n;
+            }
+            Token::Tens(n) => {
+                if !matches!(previous, None | Some(Token::Hundred | Token::Scale(_))) {
+                    return Err(misplaced());
+                }
+
+                group += n;
+            }
+            Token::Hundred => {
+                if !matches!(previous, Some(Token::Ones(_) | Token::Tens(_))) || group >= 100 {
+                    return Err(misplaced());
+                }
+
+                group *= 100;
+            }
+            Token::Scale(scale) => {
+                let descending = last_scale.is_none_or(|last| scale < last);
+
+                if group == 0 || !descending {
+                    return Err(misplaced());
+                }
+
+                let multiplier = 1000u128.checked_pow(scale as u32 + 1).ok_or(ParseWordsError::Overflow)?;
+                let value = group.checked_mul(multiplier).ok_or(ParseWordsError::Overflow)?;
+
+                total = total.checked_add(value).ok_or(ParseWordsError::Overflow)?;
+                group = 0;
+                last_scale = Some(scale);
+            }
+        }
+
+        previous = Some(token);
+    }
+
+    let magnitude = total.checked_add(group).ok_or(ParseWordsError::Overflow)?;
+
+    Ok(Number { negative: negative && magnitude > 0, magnitude, ordinal })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn number(negative: bool, magnitude: u128, ordinal: bool) -> Number {
+        Number { negative, magnitude, ordinal }
+    }
+
+    #[test]
+    fn cardinals() {
+        assert_eq!(cardinal(0), "zero");
+        assert_eq!(cardinal(13u8), "thirteen");
+        assert_eq!(cardinal(40), "forty");
+        assert_eq!(cardinal(123), "one hundred twenty-three");
+        assert_eq!(cardinal(1_000_001), "one million one");
+        assert_eq!(cardinal(-7i8), "negative seven");
+        assert_eq!(cardinal(i64::MIN), "negative nine quintillion two hundred twenty-three quadrillion three hundred seventy-two trillion thirty-six billion eight hundred fifty-four million seven hundred seventy-five thousand eight hundred eight");
+        assert!(cardinal(u128::MAX).starts_with("three hundred forty undecillion two hundred eighty-two decillion"));
+    }
+
+    #[test]
+    fn ordinals() {
+        let expected = [
+            (0, "zeroth"), (1, "first"), (2, "second"), (3, "third"), (4, "fourth"), (5, "fifth"),
+            (8, "eighth"), (9, "ninth"), (12, "twelfth"), (20, "twentieth"), (21, "twenty-first"),
+            (100, "one hundredth"), (1000, "one thousandth"),
+        ];
+
+        for (n, words) in expected {
+            assert_eq!(ordinal(n), words);
+        }
+
+        assert_eq!(ordinal(123), "one hundred twenty-third");
+        assert_eq!(ordinal(-2), "negative second");
+    }
+
+    #[test]
+    fn ordinal_suffixes() {
+        let suffixes: Vec<_> = [1, 2, 3, 4, 11, 12, 13, 21, 22, 23, 101, 111, 112, 1013, -1, -22].map(ordinal_suffix).into();
+
+        assert_eq!(suffixes, ["st", "nd", "rd", "th", "th", "th", "th", "st", "nd", "rd", "st", "th", "th", "th", "st", "nd"]);
+    }
+
+    #[test]
+    fn words_read_back_as_the_same_number() {
+        let edges = [i64::MIN as i128, i64::MAX as i128, -1_000_000, 999_999, 1_000_000_000_019];
+
+        for n in (-1100..=1100).chain((0..200).map(|i| i * 7919 + 10_000)).chain(edges) {
+            let negative = n < 0;
+
+            assert_eq!(parse(&cardinal(n)), Ok(number(negative, n.unsigned_abs(), false)), "{n}");
+            assert_eq!(parse(&ordinal(n)), Ok(number(negative, n.unsigned_abs(), true)), "{n}");
+        }
+
+        assert_eq!(parse(&cardinal(u128::MAX)), Ok(number(false, u128::MAX, false)));
+        assert_eq!(parse(&ordinal(u128::MAX)), Ok(number(false, u128::MAX, true)));
+        assert_eq!(parse(&cardinal(i64::MIN)).unwrap().to_i128(), Some(i128::from(i64::MIN)));
+    }
+
+    #[test]
+    fn other_ways_of_writing_numbers() {
+        assert_eq!(parse("One Hundred and Five"), Ok(number(false, 105, false)));
+        assert_eq!(parse("minus twenty one"), Ok(number(true, 21, false)));
+        assert_eq!(parse("two thousand, three hundred and first"), Ok(number(false, 2301, true)));
+        assert_eq!(parse("negative zero"), Ok(number(false, 0, false)));
+    }
+
+    #[test]
+    fn misplaced_words() {
+        for (words, misplaced) in [
+            ("one two", "two"),
+            ("twenty thirty", "thirty"),
+            ("twenty twelve", "twelve"),
+            ("hundred", "hundred"),
+            ("one hundred hundred", "hundred"),
+            ("thousand", "thousand"),
+            ("one thousand one million", "million"),
+            ("zero one", "zero"),
+        ] {
+            assert_eq!(parse(words), Err(ParseWordsError::Misplaced(misplaced.to_string())), "{words}");
+        }
+    }
+
+    #[test]
+    fn too_big() {
+        assert_eq!(parse("one thousand undecillion"), Err(ParseWordsError::Misplaced(String::from("undecillion"))));
+        assert_eq!(parse("nine hundred undecillion"), Err(ParseWordsError::Overflow));
+
+        // One more than u128::MAX
+        let past_max = cardinal(u128::MAX).replace("four hundred fifty-five", "four hundred fifty-six");
+        assert_eq!(parse(&past_max), Err(ParseWordsError::Overflow));
+    }
+
+    #[test]
+    fn unknown_words() {
+        assert_eq!(parse("one zillion"), Err(ParseWordsError::UnknownWord(String::from("zillion"))));
+        assert_eq!(parse("first two"), Err(ParseWordsError::UnknownWord(String::from("first"))));
+        assert_eq!(parse("  "), Err(ParseWordsError::Empty));
+    }
+
+    #[test]
+    fn misspelled_ordinals_arent_ordinals() {
+        for word in ["oneth", "twoth", "threeth", "fiveth", "eightth", "nineth", "twelveth", "twentyth", "fortyeth"] {
+            assert_eq!(parse(word), Err(ParseWordsError::UnknownWord(word.to_string())), "{word}");
+        }
+
+        assert_eq!(parse("ninth"), Ok(number(false, 9, true)));
+        assert_eq!(parse("eighth"), Ok(number(false, 8, true)));
+        assert_eq!(parse("one millionth"), Ok(number(false, 1_000_000, true)));
+    }
+}
@@ -0,0 +1,579 @@
+use std::error::Error;
+use std::fmt;
+use std::str::FromStr;
+
+/* Integer Overflow
+
+`i32::MAX + 1` panics in a debug build and quietly wraps around to `i32::MIN` in a release build.
+When overflow is possible, the chapter points at the methods that say what should happen instead:
+
+* `wrapping_*` wraps around, e.g. `250u8.wrapping_add(10)` is 4
+* `checked_*` returns `None` if the answer doesn't fit
+* `saturating_*` stops at the type's minimum or maximum, e.g. `250u8.saturating_add(10)` is 255
+* `overflowing_*` wraps, and also says whether it had to
+
+`explore` works out all four for one operation at one integer width, and `explore_all` does it for
+every width from `i8` to `u128`:
+
+    let row = explore(Width::U8, Op::Add, "250", Some("10"))?;
+    assert_eq!(row.checked, Outcome::Checked(None));
+
+*/
+
+/// An integer type
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Width {
+    I8,
+    I16,
+    I32,
+    I64,
+    I128,
+    U8,
+    U16,
+    U32,
+    U64,
+    U128,
+}
+
+impl Width {
+    pub const ALL: [Width; 10] = [
+        Width::I8, Width::I16, Width::I32, Width::I64, Width::I128,
+        Width::U8, Width::U16, Width::U32, Width::U64, Width::U128,
+    ];
+
+    pub fn bits(&self) -> u32 {
+        match self {
+            Width::I8 | Width::U8     => 8,
+            Width::I16 | Width::U16   => 16,
+            Width::I32 | Width::U32   => 32,
+            Width::I64 | Width::U64   => 64,
+            Width::I128 | Width::U128 => 128,
+        }
+    }
+
+    pub fn is_signed(&self) -> bool {
+        matches!(self, Width::I8 | Width::I16 | Width::I32 | Width::I64 | Width::I128)
+    }
+}
+
+impl fmt::Display for Width {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        write!(f, "{}{}", if self.is_signed() { "i" } else { "u" }, self.bits())
+    }
+}
+
+impl FromStr for Width {
+    type Err = OverflowError;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        Width::ALL.into_iter()
+            .find(|width| width.to_string() == s)
+            .ok_or_else(|| OverflowError::UnknownWidth(s.to_string()))
+    }
+}
+
+/// An operation that can overflow
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Op {
+    Add,
+    Sub,
+    Mul,
+    Div,
+    Rem,
+    Neg,
+    Pow,
+    Shl,
+    Shr,
+}
+
+/// What goes on the right of an operation
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Operand {
+    /// Nothing, it only takes one operand
+    None,
+    /// A number of the same type as the left
+    Same,
+    /// A `u32`, e.g. the exponent of `pow` or how far to shift
+    Amount,
+}
+
+impl Op {
+    pub const ALL: [Op; 9] = [Op::Add, Op::Sub, Op::Mul, Op::Div, Op::Rem, Op::Neg, Op::Pow, Op::Shl, Op::Shr];
+
+    /// The name of the method, after `wrapping_` and friends
+    pub fn name(&self) -> &'static str {
+        match self {
+            Op::Add => "add",
+            Op::Sub => "sub",
+            Op::Mul => "mul",
+            Op::Div => "div",
+            Op::Rem => "rem",
+            Op::Neg => "neg",
+            Op::Pow => "pow",
+            Op::Shl => "shl",
+            Op::Shr => "shr",
+        }
+    }
+
+    /// The operator, if it has one (there's no `**` in Rust, `pow` is just a method)
+    pub fn symbol(&self) -> Option<&'static str> {
+        match self {
+            Op::Add => Some("+"),
+            Op::Sub => Some("-"),
+            Op::Mul => Some("*"),
+            Op::Div => Some("/"),
+            Op::Rem => Some("%"),
+            Op::Neg => Some("-"),
+            Op::Pow => None,
+            Op::Shl => Some("<<"),
+            Op::Shr => Some(">>"),
+        }
+    }
+
+    pub fn operand(&self) -> Operand {
+        match self {
+            Op::Neg                     => Operand::None,
+            Op::Pow | Op::Shl | Op::Shr => Operand::Amount,
+            _                           => Operand::Same,
+        }
+    }
+}
+
+impl fmt::Display for Op {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        write!(f, "{}", self.name())
+    }
+}
+
+impl FromStr for Op {
+    type Err = OverflowError;
+
+    /// A method name (`add`) or an operator (`+`). `-` is always `sub`, use `neg` to negate.
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        let s = s.to_lowercase();
+
+        Op::ALL.into_iter()
+            .find(|op| op.name() == s || (*op != Op::Neg && op.symbol() == Some(s.as_str())))
+            .ok_or(OverflowError::UnknownOp(s))
+    }
+}
+
+/// An integer of any width, signed or not
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Int {
+    Signed(i128),
+    Unsigned(u128),
+}
+
+impl fmt::Display for Int {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        match self {
+            Int::Signed(n)   => write!(f, "{n}"),
+            Int::Unsigned(n) => write!(f, "{n}"),
+        }
+    }
+}
+
+/// What one of the methods does with the operands
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Outcome {
+    /// `wrapping_*` and `saturating_*` always have an answer
+    Value(Int),
+    /// `checked_*`: the answer, or `None` if it doesn't fit
+    Checked(Option<Int>),
+    /// `overflowing_*`: the wrapped answer, and whether it overflowed
+    Overflowing(Int, bool),
+    /// The method panics on these operands, e.g. dividing by zero
+    Panics,
+    /// There's no such method for the type, e.g. `saturating_rem`, or `saturating_neg` on unsigned types
+    Missing,
+}
+
+impl fmt::Display for Outcome {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        match self {
+            Outcome::Value(n)             => write!(f, "{n}"),
+            Outcome::Checked(Some(n))     => write!(f, "Some({n})"),
+            Outcome::Checked(None)        => write!(f, "None"),
+            Outcome::Overflowing(n, flag) => write!(f, "({n}, {flag})"),
+            Outcome::Panics               => write!(f, "panics"),
+            Outcome::Missing              => write!(f, "-"),
+        }
+    }
+}
+
+/// Every way of doing an operation at one width
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct Row {
+    pub width: Width,
+    pub wrapping: Outcome,
+    pub checked: Outcome,
+    pub saturating: Outcome,
+    pub overflowing: Outcome,
+}
+
+impl Row {
+    /// Whether the plain operator would have overflowed (or panicked), i.e. whether it matters which method is used
+    pub fn overflows(&self) -> bool {
+        self.checked == Outcome::Checked(None)
+    }
+}
+
+/// Something wrong with the operation or its operands
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum OverflowError {
+    UnknownOp(String),
+    UnknownWidth(String),
+    NotANumber(String),
+    /// The operand is a number, just not one the type can hold
+    DoesNotFit { operand: String, width: Width },
+    /// The shift amount or exponent isn't a `u32`
+    BadAmount(String),
+    MissingOperand(Op),
+    UnexpectedOperand(Op),
+}
+
+impl fmt::Display for OverflowError {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        match self {
+            OverflowError::UnknownOp(op)                 => write!(f, "unknown operation '{op}'"),
+            OverflowError::UnknownWidth(width)           => write!(f, "unknown integer type '{width}'"),
+            OverflowError::NotANumber(operand)           => write!(f, "'{operand}' isn't a whole number"),
+            OverflowError::DoesNotFit { operand, width } => write!(f, "{operand} doesn't fit in {width}"),
+            OverflowError::BadAmount(amount)             => write!(f, "'{amount}' isn't a u32"),
+            OverflowError::MissingOperand(op)            => write!(f, "{op} needs two operands"),
+            OverflowError::UnexpectedOperand(op)         => write!(f, "{op} only takes one operand"),
+        }
+    }
+}
+
+impl Error for OverflowError {}
+
+// The right-hand side, parsed to suit the operation
+#[derive(Debug, Clone, Copy)]
+enum Rhs<T> {
+    None,
+    Same(T),
+    Amount(u32),
+}
+
+// Every integer type can work out its own `Row`
+trait Explore: Copy + FromStr + Into<Int> {
+    const WIDTH: Width;
+
+    fn row(op: Op, a: Self, b: Rhs<Self>) -> Row;
+}
+
+// `saturating_neg` only exists for signed types, unsigned ones can't go below zero anyway
+macro_rules! saturating_neg {
+    (Signed, $a:expr)   => { Outcome::Value($a.saturating_neg().into()) };
+    (Unsigned, $a:expr) => { Outcome::Missing };
+}
+
+macro_rules! impl_explore {
+    ($kind:ident: $($t:ty => $width:ident),*) => {$(
+        impl From<$t> for Int {
+            fn from(n: $t) -> Int {
+                Int::$kind(n.into())
+            }
+        }
+
+        impl Explore for $t {
+            const WIDTH: Width = Width::$width;
+
+            fn row(op: Op, a: $t, b: Rhs<$t>) -> Row {
+                let value = |n: $t| Outcome::Value(n.into());
+                let checked = |n: Option<$t>| Outcome::Checked(n.map(Int::from));
+                let overflowing = |(n, flag): ($t, bool)| Outcome::Overflowing(n.into(), flag);
+
+                let (wrapping, checked, saturating, overflowing) = match (op, b) {
+                    // Only `checked_*` survives dividing by zero
+                    (Op::Div | Op::Rem, Rhs::Same(0)) => {
+                        let saturating = if op == Op::Div { Outcome::Panics } else { Outcome::Missing };
+                        (Outcome::Panics, checked(None), saturating, Outcome::Panics)
+                    }
+                    (Op::Add, Rhs::Same(b)) => {
+                        (value(a.wrapping_add(b)), checked(a.checked_add(b)), value(a.saturating_add(b)), overflowing(a.overflowing_add(b)))
+                    }
+                    (Op::Sub, Rhs::Same(b)) => {
+                        (value(a.wrapping_sub(b)), checked(a.checked_sub(b)), value(a.saturating_sub(b)), overflowing(a.overflowing_sub(b)))
+                    }
+                    (Op::Mul, Rhs::Same(b)) => {
+                        (value(a.wrapping_mul(b)), checked(a.checked_mul(b)), value(a.saturating_mul(b)), overflowing(a.overflowing_mul(b)))
+                    }
+                    // `MIN / -1` is the one division that overflows
+                    (Op::Div, Rhs::Same(b)) => {
+                        (value(a.wrapping_div(b)), checked(a.checked_div(b)), value(a.saturating_div(b)), overflowing(a.overflowing_div(b)))
+                    }
+                    (Op::Rem, Rhs::Same(b)) => {
+                        (value(a.wrapping_rem(b)), checked(a.checked_rem(b)), Outcome::Missing, overflowing(a.overflowing_rem(b)))
+                    }
+                    (Op::Neg, Rhs::None) => {
+                        (value(a.wrapping_neg()), checked(a.checked_neg()), saturating_neg!($kind, a), overflowing(a.overflowing_neg()))
+                    }
+                    (Op::Pow, Rhs::Amount(b)) => {
+                        (value(a.wrapping_pow(b)), checked(a.checked_pow(b)), value(a.saturating_pow(b)), overflowing(a.overflowing_pow(b)))
+                    }
+                    // Shifting overflows when the amount is at least the number of bits, not when bits fall off the end
+                    (Op::Shl, Rhs::Amount(b)) => {
+                        (value(a.wrapping_shl(b)), checked(a.checked_shl(b)), Outcome::Missing, overflowing(a.overflowing_shl(b)))
+                    }
+                    (Op::Shr, Rhs::Amount(b)) => {
+                        (value(a.wrapping_shr(b)), checked(a.checked_shr(b)), Outcome::Missing, overflowing(a.overflowing_shr(b)))
+                    }
+                    _ => unreachable!("operands are parsed to suit the operation"),
+                };
+
+                Row { width: Self::WIDTH, wrapping, checked, saturating, overflowing }
+            }
+        }
+    )*};
+}
+
+impl_explore!(Signed: i8 => I8, i16 => I16, i32 => I32, i64 => I64, i128 => I128);
+impl_explore!(Unsigned: u8 => U8, u16 => U16, u32 => U32, u64 => U64, u128 => U128);
+
+// Whether `operand` is a whole number at all, whatever the width (even one too big for all of them)
+fn is_number(operand: &str) -> bool {
+    let digits = operand.strip_prefix(['-', '+']).unwrap_or(operand);
+
+    !digits.is_empty() && digits.bytes().all(|b| b.is_ascii_digit())
+}
+
+fn parse<T: FromStr>(operand: &str, width: Width) -> Result<T, OverflowError> {
+    operand.parse().map_err(|_| match is_number(operand) {
+        true  => OverflowError::DoesNotFit { operand: operand.to_string(), width },
+        false => OverflowError::NotANumber(operand.to_string()),
+    })
+}
+
+fn explore_as<T: Explore>(op: Op, lhs: &str, rhs: Option<&str>) -> Result<Row, OverflowError> {
+    let a = parse::<T>(lhs, T::WIDTH)?;
+
+    let b = match (op.operand(), rhs) {
+        (Operand::None, None)        => Rhs::None,
+        (Operand::None, Some(_))     => return Err(OverflowError::UnexpectedOperand(op)),
+        (_, None)                    => return Err(OverflowError::MissingOperand(op)),
+        (Operand::Same, Some(rhs))   => Rhs::Same(parse::<T>(rhs, T::WIDTH)?),
+        (Operand::Amount, Some(rhs)) => Rhs::Amount(rhs.parse().map_err(|_| OverflowError::BadAmount(rhs.to_string()))?),
+    };
+
+    Ok(T::row(op, a, b))
+}
+
+/// Do `lhs op rhs` (or `op lhs`, for `neg`) every way there is at `width`. The operands are parsed
+/// as that type, so they have to fit in it.
+pub fn explore(width: Width, op: Op, lhs: &str, rhs: Option<&str>) -> Result<Row, OverflowError> {
+    match width {
+        Width::I8   => explore_as::<i8>(op, lhs, rhs),
+        Width::I16  => explore_as::<i16>(op, lhs, rhs),
+        Width::I32  => explore_as::<i32>(op, lhs, rhs),
+        Width::I64  => explore_as::<i64>(op, lhs, rhs),
+        Width::I128 => explore_as::<i128>(op, lhs, rhs),
+        Width::U8   => explore_as::<u8>(op, lhs, rhs),
+        Width::U16  => explore_as::<u16>(op, lhs, rhs),
+        Width::U32  => explore_as::<u32>(op, lhs, rhs),
+        Width::U64  => explore_as::<u64>(op, lhs, rhs),
+        Width::U128 => explore_as::<u128>(op, lhs, rhs),
+    }
+}
+
+/// `explore` at every width. Mistakes that would go wrong at every width (like an operand that
+/// isn't a number) are an error for the whole thing, and operands too big for a width are an
+/// error for just that width's row.
+pub fn explore_all(op: Op, lhs: &str, rhs: Option<&str>) -> Result<Vec<Result<Row, OverflowError>>, OverflowError> {
+    match (op.operand(), rhs) {
+        (Operand::None, Some(_))                => return Err(OverflowError::UnexpectedOperand(op)),
+        (Operand::Same | Operand::Amount, None) => return Err(OverflowError::MissingOperand(op)),
+        _                                       => {}
+    }
+
+    for operand in [Some(lhs), rhs.filter(|_| op.operand() == Operand::Same)].into_iter().flatten() {
+        if !is_number(operand) {
+            return Err(OverflowError::NotANumber(operand.to_string()));
+        }
+    }
+
+    if let (Operand::Amount, Some(rhs)) = (op.operand(), rhs) {
+        rhs.parse::<u32>().map_err(|_| OverflowError::BadAmount(rhs.to_string()))?;
+    }
+
+    Ok(Width::ALL.into_iter().map(|width| explore(width, op, lhs, rhs)).collect())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn signed(n: i128) -> Outcome {
+        Outcome::Value(Int::Signed(n))
+    }
+
+    fn unsigned(n: u128) -> Outcome {
+        Outcome::Value(Int::Unsigned(n))
+    }
+
+    fn row(width: Width, op: Op, lhs: &str, rhs: Option<&str>) -> Row {
+        explore(width, op, lhs, rhs).unwrap()
+    }
+
+    #[test]
+    fn u8_250_plus_10() {
+        assert_eq!(row(Width::U8, Op::Add, "250", Some("10")), Row {
+            width: Width::U8,
+            wrapping: unsigned(4),
+            checked: Outcome::Checked(None),
+            saturating: unsigned(255),
+            overflowing: Outcome::Overflowing(Int::Unsigned(4), true),
+        });
+    }
+
+    #[test]
+    fn u8_250_plus_10_at_every_width() {
+        let rows = explore_all(Op::Add, "250", Some("10")).unwrap();
+
+        for (width, row) in Width::ALL.into_iter().zip(rows) {
+            match width {
+                Width::I8 => assert_eq!(row, Err(OverflowError::DoesNotFit { operand: String::from("250"), width })),
+                Width::U8 => assert!(row.unwrap().overflows()),
+                _         => {
+                    let row = row.unwrap();
+                    let n = if width.is_signed() { Int::Signed(260) } else { Int::Unsigned(260) };
+
+                    assert_eq!(row.width, width);
+                    assert_eq!((row.wrapping, row.checked, row.saturating), (Outcome::Value(n), Outcome::Checked(Some(n)), Outcome::Value(n)));
+                    assert_eq!(row.overflowing, Outcome::Overflowing(n, false));
+                }
+            }
+        }
+    }
+
+    #[test]
+    fn i8_min_divided_by_minus_one() {
+        assert_eq!(row(Width::I8, Op::Div, "-128", Some("-1")), Row {
+            width: Width::I8,
+            wrapping: signed(-128),
+            checked: Outcome::Checked(None),
+            saturating: signed(127),
+            overflowing: Outcome::Overflowing(Int::Signed(-128), true),
+        });
+
+        // Only at the width where -128 is the minimum
+        assert!(!row(Width::I16, Op::Div, "-128", Some("-1")).overflows());
+        assert_eq!(row(Width::I16, Op::Div, "-128", Some("-1")).wrapping, signed(128));
+
+        let rem = row(Width::I8, Op::Rem, "-128", Some("-1"));
+        assert_eq!((rem.wrapping, rem.checked, rem.overflowing), (signed(0), Outcome::Checked(None), Outcome::Overflowing(Int::Signed(0), true)));
+    }
+
+    #[test]
+    fn i8_negating_min() {
+        assert_eq!(row(Width::I8, Op::Neg, "-128", None), Row {
+            width: Width::I8,
+            wrapping: signed(-128),
+            checked: Outcome::Checked(None),
+            saturating: signed(127),
+            overflowing: Outcome::Overflowing(Int::Signed(-128), true),
+        });
+        assert_eq!(row(Width::I8, Op::Neg, "127", None).checked, Outcome::Checked(Some(Int::Signed(-127))));
+    }
+
+    #[test]
+    fn negating_unsigned_overflows_unless_its_zero() {
+        let one = row(Width::U8, Op::Neg, "1", None);
+        assert_eq!((one.wrapping, one.checked, one.overflowing), (unsigned(255), Outcome::Checked(None), Outcome::Overflowing(Int::Unsigned(255), true)));
+
+        let zero = row(Width::U32, Op::Neg, "0", None);
+        assert_eq!((zero.wrapping, zero.checked, zero.overflowing), (unsigned(0), Outcome::Checked(Some(Int::Unsigned(0))), Outcome::Overflowing(Int::Unsigned(0), false)));
+    }
+
+    #[test]
+    fn shifting_by_at_least_the_number_of_bits() {
+        for width in Width::ALL {
+            let bits = width.bits().to_string();
+            let row = row(width, Op::Shl, "1", Some(&bits));
+            let one = if width.is_signed() { signed(1) } else { unsigned(1) };
+
+            // The amount wraps around to 0, so nothing moves
+            assert_eq!(row.wrapping, one, "{width}");
+            assert_eq!(row.checked, Outcome::Checked(None), "{width}");
+            assert!(matches!(row.overflowing, Outcome::Overflowing(_, true)), "{width}");
+        }
+
+        assert_eq!(row(Width::U32, Op::Shr, "8", Some("35")).wrapping, unsigned(1));
+        assert_eq!(row(Width::U32, Op::Shr, "8", Some("35")).checked, Outcome::Checked(None));
+    }
+
+    #[test]
+    fn shifting_bits_off_the_end_isnt_overflow() {
+        let row = row(Width::U8, Op::Shl, "255", Some("7"));
+
+        assert_eq!(row.checked, Outcome::Checked(Some(Int::Unsigned(128))));
+        assert_eq!(row.overflowing, Outcome::Overflowing(Int::Unsigned(128), false));
+        assert!(!row.overflows());
+    }
+
+    #[test]
+    fn dividing_by_zero() {
+        for width in Width::ALL {
+            assert_eq!(row(width, Op::Div, "7", Some("0")), Row {
+                width,
+                wrapping: Outcome::Panics,
+                checked: Outcome::Checked(None),
+                saturating: Outcome::Panics,
+                overflowing: Outcome::Panics,
+            });
+            assert_eq!(row(width, Op::Rem, "7", Some("0")), Row {
+                width,
+                wrapping: Outcome::Panics,
+                checked: Outcome::Checked(None),
+                saturating: Outcome::Missing,
+                overflowing: Outcome::Panics,
+            });
+        }
+    }
+
+    #[test]
+    fn missing_methods() {
+        assert_eq!(row(Width::I32, Op::Rem, "7", Some("2")).saturating, Outcome::Missing);
+        assert_eq!(row(Width::U16, Op::Shl, "1", Some("2")).saturating, Outcome::Missing);
+        assert_eq!(row(Width::I64, Op::Shr, "4", Some("1")).saturating, Outcome::Missing);
+        assert_eq!(row(Width::U8, Op::Neg, "0", None).saturating, Outcome::Missing);
+        assert_eq!(row(Width::I8, Op::Neg, "5", None).saturating, signed(-5));
+    }
+
+    #[test]
+    fn pow() {
+        let row = row(Width::U8, Op::Pow, "2", Some("8"));
+
+        assert_eq!((row.wrapping, row.checked, row.saturating), (unsigned(0), Outcome::Checked(None), unsigned(255)));
+    }
+
+    #[test]
+    fn explore_all_errors() {
+        assert_eq!(explore_all(Op::Add, "ten", Some("1")), Err(OverflowError::NotANumber(String::from("ten"))));
+        assert_eq!(explore_all(Op::Add, "1", Some("1.5")), Err(OverflowError::NotANumber(String::from("1.5"))));
+        assert_eq!(explore_all(Op::Shl, "1", Some("-1")), Err(OverflowError::BadAmount(String::from("-1"))));
+        assert_eq!(explore_all(Op::Pow, "2", Some("4294967296")), Err(OverflowError::BadAmount(String::from("4294967296"))));
+        assert_eq!(explore_all(Op::Add, "1", None), Err(OverflowError::MissingOperand(Op::Add)));
+        assert_eq!(explore_all(Op::Neg, "1", Some("2")), Err(OverflowError::UnexpectedOperand(Op::Neg)));
+    }
+
+    #[test]
+    fn explore_all_doesnt_fit_is_per_width() {
+        let rows = explore_all(Op::Sub, "-1", Some("300")).unwrap();
+
+        for (width, row) in Width::ALL.into_iter().zip(rows) {
+            match width {
+                Width::I8 => assert_eq!(row, Err(OverflowError::DoesNotFit { operand: String::from("300"), width })),
+                _ if !width.is_signed() => assert_eq!(row, Err(OverflowError::DoesNotFit { operand: String::from("-1"), width })),
+                _ => assert_eq!(row.unwrap().checked, Outcome::Checked(Some(Int::Signed(-301)))),
+            }
+        }
+
+        // A number too big for every width still isn't "not a number"
+        let too_big = u128::MAX.to_string() + "0";
+        let rows = explore_all(Op::Add, &too_big, Some("1")).unwrap();
+        assert!(rows.iter().all(|row| matches!(row, Err(OverflowError::DoesNotFit { .. }))));
+    }
+}
@@ -0,0 +1,438 @@
+use std::cell::{Cell, RefCell};
+use std::error::Error;
+use std::fmt;
+use std::thread;
+use std::time::{Duration, Instant};
+
+/* Retry
+
+The `'counting_up` labeled loop in `main.rs`, grown up: keep calling something that can fail
+until it works, waiting a little longer between attempts, and stop early when there's no point
+going on.
+
+    let value = Retry::new(5)
+        .backoff(Backoff::exponential(Duration::from_millis(100), Duration::from_secs(2)))
+        .give_up_if(|err: &io::Error| err.kind() == io::ErrorKind::NotFound)
+        .run(|attempt| fetch(attempt))?;
+
+Waiting goes through a `Clock`, so `ManualClock` can stand in for the real one and make retries
+instant (and checkable).
+
+*/
+
+/// Tells the time and waits
+pub trait Clock {
+    /// Time since some fixed point, e.g. when the clock was made
+    fn now(&self) -> Duration;
+
+    fn sleep(&self, duration: Duration);
+}
+
+impl<C: Clock + ?Sized> Clock for &C {
+    fn now(&self) -> Duration {
+        (**self).now()
+    }
+
+    fn sleep(&self, duration: Duration) {
+        (**self).sleep(duration)
+    }
+}
+
+/// The real clock, which really sleeps
+#[derive(Debug, Clone, Copy)]
+pub struct SystemClock {
+    started: Instant,
+}
+
+impl Default for SystemClock {
+    fn default() -> Self {
+        SystemClock::new()
+    }
+}
+
+impl SystemClock {
+    pub fn new() -> Self {
+        SystemClock { started: Instant::now() }
+    }
+}
+
+impl Clock for SystemClock {
+    fn now(&self) -> Duration {
+        self.started.elapsed()
+    }
+
+    fn sleep(&self, duration: Duration) {
+        thread::sleep(duration);
+    }
+}
+
+/// A clock that only moves when it's told to sleep, and remembers every sleep
+#[derive(Debug, Default)]
+pub struct ManualClock {
+    now: Cell<Duration>,
+    sleeps: RefCell<Vec<Duration>>,
+}
+
+impl ManualClock {
+    pub fn new() -> Self {
+        ManualClock::default()
+    }
+
+    /// Move the clock on without counting it as a sleep, e.g. for time spent in an attempt
+    pub fn advance(&self, duration: Duration) {
+        self.now.set(self.now.get() + duration);
+    }
+
+    /// Every sleep so far, oldest first
+    pub fn sleeps(&self) -> Vec<Duration> {
+        self.sleeps.borrow().clone()
+    }
+}
+
+impl Clock for ManualClock {
+    fn now(&self) -> Duration {
+        self.now.get()
+    }
+
+    fn sleep(&self, duration: Duration) {
+        self.advance(duration);
+        self.sleeps.borrow_mut().push(duration);
+    }
+}
+
+/// How long to wait before each retry
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum Backoff {
+    /// Don't wait at all
+    None,
+    /// The same wait every time
+    Fixed(Duration),
+    /// `initial`, then twice that, then twice that again, ... but never more than `max`
+    Exponential { initial: Duration, max: Duration },
+    /// Exponential backoff, but each wait is picked at random between zero and the exponential
+    /// wait ("full jitter"), so lots of clients retrying at once spread out. `seed` makes the
+    /// waits repeatable.
+    Jittered { initial: Duration, max: Duration, seed: u64 },
+}
+
+impl Backoff {
+    pub fn exponential(initial: Duration, max: Duration) -> Self {
+        Backoff::Exponential { initial, max }
+    }
+
+    pub fn jittered(initial: Duration, max: Duration, seed: u64) -> Self {
+        Backoff::Jittered { initial, max, seed }
+    }
+
+    /// The wait after failed attempt number `attempt` (counting from 1)
+    pub fn delay(&self, attempt: u32) -> Duration {
+        // Anything that overflows is well past `max` anyway
+        let doubled = |initial: Duration, max: Duration| {
+            2u32.checked_pow(attempt.saturating_sub(1))
+                .and_then(|factor| initial.checked_mul(factor))
+                .map_or(max, |delay| delay.min(max))
+        };
+
+        match *self {
+            Backoff::None                         => Duration::ZERO,
+            Backoff::Fixed(delay)                 => delay,
+            Backoff::Exponential { initial, max } => doubled(initial, max),
+            Backoff::Jittered { initial, max, seed } => {
+                let ceiling = doubled(initial, max);
+                let fraction = random_fraction(seed, attempt);
+
+                ceiling.mul_f64(fraction)
+            }
+        }
+    }
+}
+
+// A repeatable "random" number in [0, 1) for the `attempt`th wait from `seed` (splitmix64)
+fn random_fraction(seed: u64, attempt: u32) -> f64 {
+    let mut z = seed.wrapping_add(u64::from(attempt).wrapping_mul(0x9E37_79B9_7F4A_7C15));
+    z = (z ^ (z >> 30)).wrapping_mul(0xBF58_476D_1CE4_E5B9);
+    z = (z ^ (z >> 27)).wrapping_mul(0x94D0_49BB_1331_11EB);
+    z ^= z >> 31;
+
+    (z >> 11) as f64 / (1u64 << 53) as f64
+}
+
+/// Why `Retry::run` stopped without a success. Either way, the last error is kept.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum RetryError<E> {
+    /// Every attempt failed
+    Exhausted { attempts: u32, error: E },
+    /// `give_up_if` said this error wasn't worth retrying
+    GaveUp { attempts: u32, error: E },
+    /// The next wait would have gone past the deadline
+    TimedOut { attempts: u32, error: E },
+}
+
+impl<E> RetryError<E> {
+    pub fn attempts(&self) -> u32 {
+        match self {
+            RetryError::Exhausted { attempts, .. }
+            | RetryError::GaveUp { attempts, .. }
+            | RetryError::TimedOut { attempts, .. } => *attempts,
+        }
+    }
+
+    /// The error from the last attempt
+    pub fn into_inner(self) -> E {
+        match self {
+            RetryError::Exhausted { error, .. } | RetryError::GaveUp { error, .. } | RetryError::TimedOut { error, .. } => error,
+        }
+    }
+}
+
+impl<E: fmt::Display> fmt::Display for RetryError<E> {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        match self {
+            RetryError::Exhausted { attempts, error } => write!(f, "failed {attempts} times, last with: {error}"),
+            RetryError::GaveUp { attempts, error }    => write!(f, "gave up after {attempts} attempts: {error}"),
+            RetryError::TimedOut { attempts, error }  => write!(f, "ran out of time after {attempts} attempts: {error}"),
+        }
+    }
+}
+
+impl<E: fmt::Debug + fmt::Display> Error for RetryError<E> {}
+
+/// Decides from an error whether to stop retrying
+type GiveUpIf<'a, E> = Box<dyn Fn(&E) -> bool + 'a>;
+
+/// Settings for retrying something that can fail
+pub struct Retry<'a, E> {
+    max_attempts: u32,
+    backoff: Backoff,
+    deadline: Option<Duration>,
+    give_up_if: Option<GiveUpIf<'a, E>>,
+    clock: Box<dyn Clock + 'a>,
+}
+
+impl<E> fmt::Debug for Retry<'_, E> {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        f.debug_struct("Retry")
+            .field("max_attempts", &self.max_attempts)
+            .field("backoff", &self.backoff)
+            .field("deadline", &self.deadline)
+            .field("give_up_if", &self.give_up_if.is_some())
+            .finish()
+    }
+}
+
+impl<'a, E> Retry<'a, E> {
+    /// Try up to `max_attempts` times (at least once), without waiting in between
+    pub fn new(max_attempts: u32) -> Self {
+        Retry {
+            max_attempts: max_attempts.max(1),
+            backoff: Backoff::None,
+            deadline: None,
+            give_up_if: None,
+            clock: Box::new(SystemClock::new()),
+        }
+    }
+
+    pub fn backoff(mut self, backoff: Backoff) -> Self {
+        self.backoff = backoff;
+        self
+    }
+
+    /// Don't start a wait that would end more than `deadline` after the first attempt
+    pub fn deadline(mut self, deadline: Duration) -> Self {
+        self.deadline = Some(deadline);
+        self
+    }
+
+    /// Stop straight away on errors that retrying won't fix
+    pub fn give_up_if(mut self, give_up_if: impl Fn(&E) -> bool + 'a) -> Self {
+        self.give_up_if = Some(Box::new(give_up_if));
+        self
+    }
+
+    /// Tell the time and wait with `clock` instead of the system clock
+    pub fn clock(mut self, clock: impl Clock + 'a) -> Self {
+        self.clock = Box::new(clock);
+        self
+    }
+
+    /// Call `operation` (with the attempt number, counting from 1) until it succeeds or one of
+    /// the limits is hit
+    pub fn run<T>(&self, mut operation: impl FnMut(u32) -> Result<T, E>) -> Result<T, RetryError<E>> {
+        let started = self.clock.now();
+        let mut attempt = 0;
+
+        'attempts: loop {
+            attempt += 1;
+
+            let error = match operation(attempt) {
+                Ok(value) => return Ok(value),
+                Err(error) => error,
+            };
+
+            if self.give_up_if.as_ref().is_some_and(|give_up_if| give_up_if(&error)) {
+                break 'attempts Err(RetryError::GaveUp { attempts: attempt, error });
+            }
+
+            if attempt >= self.max_attempts {
+                break 'attempts Err(RetryError::Exhausted { attempts: attempt, error });
+            }
+
+            let delay = self.backoff.delay(attempt);
+            let past_deadline = self.deadline.is_some_and(|deadline| {
+                self.clock.now().saturating_sub(started) + delay > deadline
+            });
+
+            if past_deadline {
+                break 'attempts Err(RetryError::TimedOut { attempts: attempt, error });
+            }
+
+            if !delay.is_zero() {
+                self.clock.sleep(delay);
+            }
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn ms(millis: u64) -> Duration {
+        Duration::from_millis(millis)
+    }
+
+    // Fails with the attempt number every time
+    fn always_fails(attempt: u32) -> Result<(), u32> {
+        Err(attempt)
+    }
+
+    #[test]
+    fn fixed_and_no_backoff() {
+        assert_eq!((1..=3).map(|attempt| Backoff::Fixed(ms(250)).delay(attempt)).collect::<Vec<_>>(), [ms(250); 3]);
+        assert_eq!(Backoff::None.delay(7), Duration::ZERO);
+    }
+
+    #[test]
+    fn exponential_backoff_doubles_up_to_max() {
+        let backoff = Backoff::exponential(ms(100), ms(1000));
+        let delays: Vec<_> = (1..=6).map(|attempt| backoff.delay(attempt)).collect();
+
+        assert_eq!(delays, [ms(100), ms(200), ms(400), ms(800), ms(1000), ms(1000)]);
+
+        // Far enough along that doubling would overflow
+        assert_eq!(backoff.delay(40), ms(1000));
+        assert_eq!(backoff.delay(u32::MAX), ms(1000));
+    }
+
+    #[test]
+    fn jittered_backoff_stays_under_the_exponential_wait() {
+        let jittered = Backoff::jittered(ms(100), ms(1000), 42);
+        let exponential = Backoff::exponential(ms(100), ms(1000));
+
+        for attempt in 1..=20 {
+            assert!(jittered.delay(attempt) < exponential.delay(attempt), "attempt {attempt}");
+        }
+
+        // Not just zero (or the same fraction) every time
+        let delays: Vec<_> = (1..=20).map(|attempt| jittered.delay(attempt)).collect();
+        assert!(delays.iter().any(|delay| *delay > ms(500)));
+        assert!(delays.windows(2).any(|pair| pair[0] != pair[1]));
+    }
+
+    #[test]
+    fn jitter_follows_the_seed() {
+        let delays = |seed| (1..=10).map(|attempt| Backoff::jittered(ms(100), ms(5000), seed).delay(attempt)).collect::<Vec<_>>();
+
+        assert_eq!(delays(7), delays(7));
+        assert_ne!(delays(7), delays(8));
+    }
+
+    #[test]
+    fn success_on_a_later_attempt_returns_its_value() {
+        let clock = ManualClock::new();
+        let result = Retry::new(5)
+            .backoff(Backoff::exponential(ms(100), ms(1000)))
+            .clock(&clock)
+            .run(|attempt| if attempt == 3 { Ok(attempt * 10) } else { Err("not yet") });
+
+        assert_eq!(result, Ok(30));
+        assert_eq!(clock.sleeps(), [ms(100), ms(200)]);
+        assert_eq!(clock.now(), ms(300));
+    }
+
+    #[test]
+    fn success_first_time_doesnt_wait() {
+        let clock = ManualClock::new();
+        let result: Result<_, RetryError<()>> = Retry::new(5).backoff(Backoff::Fixed(ms(100))).clock(&clock).run(|_| Ok("done"));
+
+        assert_eq!(result, Ok("done"));
+        assert!(clock.sleeps().is_empty());
+    }
+
+    #[test]
+    fn exhausted_keeps_the_last_error() {
+        let clock = ManualClock::new();
+        let result = Retry::new(4).backoff(Backoff::Fixed(ms(50))).clock(&clock).run(always_fails);
+
+        assert_eq!(result, Err(RetryError::Exhausted { attempts: 4, error: 4 }));
+        assert_eq!(clock.sleeps(), [ms(50); 3]);
+    }
+
+    #[test]
+    fn at_least_one_attempt() {
+        let clock = ManualClock::new();
+
+        assert_eq!(Retry::new(0).clock(&clock).run(always_fails), Err(RetryError::Exhausted { attempts: 1, error: 1 }));
+    }
+
+    #[test]
+    fn gives_up_on_errors_that_retrying_wont_fix() {
+        let clock = ManualClock::new();
+        let result = Retry::new(10)
+            .backoff(Backoff::Fixed(ms(50)))
+            .give_up_if(|error: &u32| *error == 3)
+            .clock(&clock)
+            .run(always_fails);
+
+        assert_eq!(result, Err(RetryError::GaveUp { attempts: 3, error: 3 }));
+        assert_eq!(clock.sleeps(), [ms(50); 2]);
+    }
+
+    #[test]
+    fn times_out_instead_of_waiting_past_the_deadline() {
+        let clock = ManualClock::new();
+        let result = Retry::new(10)
+            .backoff(Backoff::exponential(ms(100), ms(10_000)))
+            .deadline(ms(1000))
+            .clock(&clock)
+            .run(always_fails);
+
+        // 100 + 200 + 400 fits in a second, another 800 doesn't
+        assert_eq!(result, Err(RetryError::TimedOut { attempts: 4, error: 4 }));
+        assert_eq!(clock.sleeps(), [ms(100), ms(200), ms(400)]);
+    }
+
+    #[test]
+    fn time_spent_in_attempts_counts_towards_the_deadline() {
+        let clock = ManualClock::new();
+        let result = Retry::new(10)
+            .backoff(Backoff::Fixed(ms(100)))
+            .deadline(ms(1000))
+            .clock(&clock)
+            .run(|attempt| {
+                clock.advance(ms(300));
+                always_fails(attempt)
+            });
+
+        // 300, 700 and 1100 after the start, and the third can't wait another 100
+        assert_eq!(result.map_err(|err| err.attempts()), Err(3));
+        assert_eq!(clock.sleeps(), [ms(100); 2]);
+    }
+
+    #[test]
+    fn errors_say_why_they_stopped() {
+        assert_eq!(RetryError::Exhausted { attempts: 3, error: "nope" }.to_string(), "failed 3 times, last with: nope");
+        assert_eq!(RetryError::GaveUp { attempts: 1, error: "nope" }.to_string(), "gave up after 1 attempts: nope");
+        assert_eq!(RetryError::TimedOut { attempts: 2, error: "nope" }.into_inner(), "nope");
+    }
+}
@@ -0,0 +1,150 @@
+use std::cmp::Ordering;
+use std::fmt;
+
+/* Sign
+
+Which side of zero a number is on. Zero gets its own answer instead of being lumped in with the
+positive (or negative) numbers, and floats get the awkward cases right:
+
+- `-0.0` is zero (IEEE 754 says `-0.0 == 0.0`), even though its sign bit is set
+- infinities are as positive or negative as any other number
+- NaN isn't on either side of zero, or on it, so it has no sign at all
+
+*/
+
+/// Below, at, or above zero
+#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord, Hash)]
+pub enum Sign {
+    Negative,
+    Zero,
+    Positive,
+}
+
+impl From<Ordering> for Sign {
+    // `x.cmp(&0)` already says which side of zero `x` is on
+    fn from(ordering: Ordering) -> Self {
+        match ordering {
+            Ordering::Less    => Sign::Negative,
+            Ordering::Equal   => Sign::Zero,
+            Ordering::Greater => Sign::Positive,
+        }
+    }
+}
+
+impl fmt::Display for Sign {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        match self {
+            Sign::Negative => write!(f, "negative"),
+            Sign::Zero     => write!(f, "zero"),
+            Sign::Positive => write!(f, "positive"),
+        }
+    }
+}
+
+/// Numbers that can say which side of zero they're on
+pub trait HasSign: Copy {
+    /// The sign, or `None` for NaN
+    fn sign(self) -> Option<Sign>;
+
+    /// Above zero. Zero itself isn't positive.
+    fn is_positive(self) -> bool {
+        self.sign() == Some(Sign::Positive)
+    }
+
+    /// Below zero. `-0.0` isn't negative.
+    fn is_negative(self) -> bool {
+        self.sign() == Some(Sign::Negative)
+    }
+
+    /// Exactly zero, including `-0.0`
+    fn is_zero(self) -> bool {
+        self.sign() == Some(Sign::Zero)
+    }
+}
+
+macro_rules! impl_integer {
+    ($($t:ty)*) => {$(
+        impl HasSign for $t {
+            // Integers always have a sign
+            fn sign(self) -> Option<Sign> {
+                Some(Sign::from(self.cmp(&0)))
+            }
+        }
+    )*};
+}
+
+macro_rules! impl_float {
+    ($($t:ty)*) => {$(
+        impl HasSign for $t {
+            // `partial_cmp` treats -0.0 as equal to 0.0, and has no answer for NaN
+            fn sign(self) -> Option<Sign> {
+                self.partial_cmp(&0.0).map(Sign::from)
+            }
+        }
+    )*};
+}
+
+impl_integer!(i8 i16 i32 i64 i128 isize u8 u16 u32 u64 u128 usize);
+impl_float!(f32 f64);
+
+/// The sign of `x`, or `None` for NaN
+pub fn sign(x: impl HasSign) -> Option<Sign> {
+    x.sign()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    // Floats have their own (deprecated) `is_positive` and `is_negative`, which win over the trait's
+    // and count -0.0 as negative, so those go through `HasSign::` by name
+
+    #[test]
+    fn negative_zero_is_zero() {
+        assert_eq!((-0.0f64).sign(), Some(Sign::Zero));
+        assert_eq!((-0.0f32).sign(), Some(Sign::Zero));
+        assert!(!HasSign::is_negative(-0.0f64));
+        assert!((-0.0f64).is_zero());
+    }
+
+    #[test]
+    fn nan_has_no_sign() {
+        assert_eq!(f64::NAN.sign(), None);
+        assert_eq!(sign(-f32::NAN), None);
+        assert!(!HasSign::is_positive(f64::NAN) && !HasSign::is_negative(f64::NAN) && !f64::NAN.is_zero());
+    }
+
+    #[test]
+    fn infinities() {
+        assert_eq!(f64::INFINITY.sign(), Some(Sign::Positive));
+        assert_eq!(f64::NEG_INFINITY.sign(), Some(Sign::Negative));
+        assert_eq!(f32::INFINITY.sign(), Some(Sign::Positive));
+        assert_eq!(f32::NEG_INFINITY.sign(), Some(Sign::Negative));
+    }
+
+    #[test]
+    fn integers() {
+        assert_eq!(0u32.sign(), Some(Sign::Zero));
+        assert_eq!(0usize.sign(), Some(Sign::Zero));
+        assert_eq!(u8::MAX.sign(), Some(Sign::Positive));
+        assert_eq!(i64::MIN.sign(), Some(Sign::Negative));
+        assert_eq!((-1i8).sign(), Some(Sign::Negative));
+    }
+
+    #[test]
+    fn zero_is_neither_positive_nor_negative() {
+        assert!(!0.is_positive());
+        assert!(!0.is_negative());
+        assert!(0.is_zero());
+        assert!(!HasSign::is_positive(0.0f64));
+        assert!(5.is_positive());
+        assert!(HasSign::is_positive(f64::MIN_POSITIVE));
+    }
+
+    #[test]
+    fn signs_order_and_display() {
+        assert!(Sign::Negative < Sign::Zero && Sign::Zero < Sign::Positive);
+        assert_eq!(Sign::from(3.cmp(&7)), Sign::Negative);
+        assert_eq!(Sign::Zero.to_string(), "zero");
+    }
+}
@@ -0,0 +1,300 @@
+use crate::number_words::{cardinal, ordinal};
+use crate::twelve_days;
+
+/* Songs
+
+Songs that repeat themselves, described instead of written out. A `Song` is a verse template and a
+rule for what changes from one verse to the next:
+
+- `Verses::Each`: every verse sings its own item (This Old Man, Old MacDonald)
+- `Verses::Cumulative`: every verse sings its own item and then all the ones before it (The
+  Twelve Days of Christmas)
+- `Verses::Countdown`: every verse counts one down from the last (99 Bottles of Beer)
+
+Templates fill in `{number}` (the verse number), `{cardinal}` ("three"), `{ordinal}` ("third")
+and the fields of the verse's item as `{0}`, `{1}`, ... The first letter of every line is
+capitalized, so templates can start lines with a placeholder.
+
+*/
+
+/// A song made of verses that follow a pattern
+#[derive(Debug, Clone, Copy)]
+pub struct Song {
+    /// What to ask for it by, e.g. "99-bottles"
+    pub name: &'static str,
+    pub title: &'static str,
+    pub verse: &'static str,
+    pub verses: Verses,
+}
+
+/// What changes from one verse to the next
+#[derive(Debug, Clone, Copy)]
+pub enum Verses {
+    /// One verse per item
+    Each {
+        items: &'static [&'static [&'static str]],
+    },
+    /// One verse per item. `{lines}` in the verse is this verse's item followed by every earlier
+    /// one, each through `line`, except the very first item, which goes through `first` (and
+    /// gets `and` in front once it's no longer alone). Lines are separated by `,` and a line break.
+    Cumulative {
+        items: &'static [&'static [&'static str]],
+        line: &'static str,
+        first: &'static str,
+        and: &'static str,
+    },
+    /// One verse per number from `from` down to 1, then `last`. `{count}` is the number with
+    /// `one` or `many` after it, and `{next}` is the count one verse later. The last verse's
+    /// count is `none`, and its `{next}` wraps back round to `from`.
+    Countdown {
+        from: u32,
+        one: &'static str,
+        many: &'static str,
+        none: &'static str,
+        last: &'static str,
+    },
+}
+
+/// Every song there is to sing
+pub const SONGS: &[Song] = &[
+    twelve_days::SONG,
+    Song {
+        name: "old-macdonald",
+        title: "Old MacDonald Had a Farm",
+        verse: "Old MacDonald had a farm, E-I-E-I-O!\n\
+                And on that farm he had a {0}, E-I-E-I-O!\n\
+                With a {1} {1} here and a {1} {1} there,\n\
+                Here a {1}, there a {1}, everywhere a {1} {1}.\n\
+                Old MacDonald had a farm, E-I-E-I-O!",
+        verses: Verses::Each {
+            items: &[
+                &["cow", "moo"],
+                &["pig", "oink"],
+                &["duck", "quack"],
+                &["horse", "neigh"],
+                &["sheep", "baa"],
+            ],
+        },
+    },
+    Song {
+        name: "99-bottles",
+        title: "99 Bottles of Beer",
+        verse: "{count} of beer on the wall, {count} of beer.\n\
+                Take one down and pass it around, {next} of beer on the wall.",
+        verses: Verses::Countdown {
+            from: 99,
+            one: "bottle",
+            many: "bottles",
+            none: "no more bottles",
+            last: "{count} of beer on the wall, {count} of beer.\n\
+                   Go to the store and buy some more, {next} of beer on the wall.",
+        },
+    },
+    Song {
+        name: "this-old-man",
+        title: "This Old Man",
+        verse: "This old man, he played {cardinal},\n\
+                He played knick-knack {0};\n\
+                With a knick-knack paddywhack,\n\
+                Give the dog a bone,\n\
+                This old man came rolling home.",
+        verses: Verses::Each {
+            items: &[
+                &["on my thumb"],
+                &["on my shoe"],
+                &["on my knee"],
+                &["on my door"],
+                &["on my hive"],
+                &["on my sticks"],
+                &["up in heaven"],
+                &["on my gate"],
+                &["on my spine"],
+                &["once again"],
+            ],
+        },
+    },
+];
+
+/// Look a song up by its `name`
+pub fn by_name(name: &str) -> Option<&'static Song> {
+    SONGS.iter().find(|song| song.name == name)
+}
+
+fn capitalize(line: &str) -> String {
+    let mut chars = line.chars();
+
+    match chars.next() {
+        Some(first) => first.to_uppercase().chain(chars).collect(),
+        None        => String::new(),
+    }
+}
+
+// Swap every `{key}` in `template` for its value
+fn fill(template: &str, values: &[(&str, &str)]) -> String {
+    values.iter().fold(template.to_string(), |text, (key, value)| text.replace(&format!("{{{key}}}"), value))
+}
+
+// Fill in the verse number and the fields of an item
+fn fill_item(template: &str, number: usize, item: &[&str]) -> String {
+    let fields: Vec<String> = (0..item.len()).map(|i| i.to_string()).collect();
+
+    let mut values = vec![
+        ("number", number.to_string()),
+        ("cardinal", cardinal(number)),
+        ("ordinal", ordinal(number)),
+    ];
+    values.extend(fields.iter().map(String::as_str).zip(item.iter().map(|field| field.to_string())));
+
+    let values: Vec<(&str, &str)> = values.iter().map(|(key, value)| (*key, value.as_str())).collect();
+
+    fill(template, &values)
+}
+
+/// Capitalize the first letter of every line
+pub(crate) fn capitalize_lines(verse: &str) -> String {
+    verse.lines().map(capitalize).collect::<Vec<_>>().join("\n")
+}
+
+/// Verse `number` of a cumulative song before capitalizing, for items that don't have to be
+/// `'static` (see `Verses::Cumulative`)
+pub(crate) fn cumulative_verse(verse: &str, items: &[&[&str]], number: usize, line: &str, first: &str, and: &str) -> String {
+    let lines: Vec<String> = (1..=number)
+        .rev()
+        .map(|current| match current {
+            1 if number > 1 => format!("{and}{}", fill_item(first, current, items[0])),
+            1               => fill_item(first, current, items[0]),
+            _               => fill_item(line, current, items[current - 1]),
+        })
+        .collect();
+
+    fill(&fill_item(verse, number, items[number - 1]), &[("lines", &lines.join(",\n"))])
+}
+
+impl Song {
+    /// How many verses the song has
+    pub fn len(&self) -> usize {
+        match self.verses {
+            Verses::Each { items } | Verses::Cumulative { items, .. } => items.len(),
+            Verses::Countdown { from, .. }                           => from as usize + 1,
+        }
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.len() == 0
+    }
+
+    /// Verse `number`, counting from 1. Panics past the end of the song.
+    pub fn verse(&self, number: usize) -> String {
+        assert!((1..=self.len()).contains(&number), "{} has no verse {number}", self.name);
+
+        let verse = match self.verses {
+            Verses::Each { items } => fill_item(self.verse, number, items[number - 1]),
+            Verses::Cumulative { items, line, first, and } => cumulative_verse(self.verse, items, number, line, first, and),
+            Verses::Countdown { from, one, many, none, last } => {
+                let count = |n: u32| match n {
+                    0 => none.to_string(),
+                    1 => format!("1 {one}"),
+                    _ => format!("{n} {many}"),
+                };
+
+                // Verse 1 counts from `from`, and the one after 1 is the last
+                let left = from - (number as u32 - 1);
+
+                match left {
+                    0 => fill(last, &[("count", &count(0)), ("next", &count(from))]),
+                    _ => fill(self.verse, &[("count", &count(left)), ("next", &count(left - 1))]),
+                }
+            }
+        };
+
+        capitalize_lines(&verse)
+    }
+
+    /// The whole song, verses separated by blank lines
+    pub fn lyrics(&self) -> String {
+        (1..=self.len())
+            .map(|number| self.verse(number))
+            .collect::<Vec<_>>()
+            .join("\n\n")
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn song(name: &str) -> &'static Song {
+        by_name(name).unwrap()
+    }
+
+    #[test]
+    fn bottles_count_down_from_99() {
+        let bottles = song("99-bottles");
+
+        assert_eq!(bottles.len(), 100);
+        assert_eq!(bottles.verse(1), "\
+99 bottles of beer on the wall, 99 bottles of beer.
+Take one down and pass it around, 98 bottles of beer on the wall.");
+        assert_eq!(bottles.verse(98), "\
+2 bottles of beer on the wall, 2 bottles of beer.
+Take one down and pass it around, 1 bottle of beer on the wall.");
+    }
+
+    #[test]
+    fn one_bottle() {
+        assert_eq!(song("99-bottles").verse(99), "\
+1 bottle of beer on the wall, 1 bottle of beer.
+Take one down and pass it around, no more bottles of beer on the wall.");
+    }
+
+    #[test]
+    fn no_more_bottles_goes_back_to_99() {
+        let bottles = song("99-bottles");
+
+        assert_eq!(bottles.verse(100), "\
+No more bottles of beer on the wall, no more bottles of beer.
+Go to the store and buy some more, 99 bottles of beer on the wall.");
+        assert!(bottles.lyrics().ends_with(&bottles.verse(100)));
+    }
+
+    #[test]
+    fn each_verse_sings_its_own_item() {
+        let old_man = song("this-old-man");
+
+        assert_eq!(old_man.len(), 10);
+        assert_eq!(old_man.verse(1), "\
+This old man, he played one,
+He played knick-knack on my thumb;
+With a knick-knack paddywhack,
+Give the dog a bone,
+This old man came rolling home.");
+        assert!(old_man.verse(10).starts_with("This old man, he played ten,\nHe played knick-knack once again;"));
+
+        assert_eq!(song("old-macdonald").verse(3).lines().nth(2), Some("With a quack quack here and a quack quack there,"));
+    }
+
+    #[test]
+    fn lyrics_separate_verses_with_blank_lines() {
+        let old_man = song("this-old-man");
+
+        assert_eq!(old_man.lyrics().split("\n\n").count(), old_man.len());
+        assert_eq!(old_man.lyrics().split("\n\n").nth(4), Some(old_man.verse(5).as_str()));
+    }
+
+    #[test]
+    #[should_panic(expected = "this-old-man has no verse 11")]
+    fn there_are_no_verses_past_the_end() {
+        song("this-old-man").verse(11);
+    }
+
+    #[test]
+    fn songs_by_name() {
+        assert_eq!(by_name("99-bottles").map(|song| song.title), Some("99 Bottles of Beer"));
+        assert!(by_name("wonderwall").is_none());
+        assert!(by_name("").is_none());
+
+        for song in SONGS {
+            assert_eq!(by_name(song.name).map(|found| found.title), Some(song.title));
+        }
+    }
+}
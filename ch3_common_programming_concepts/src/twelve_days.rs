@@ -0,0 +1,163 @@
+use std::fs;
+use std::io;
+use std::path::Path;
+
+use crate::songs::{self, Song, Verses};
+
+/* The Twelve Days of Christmas
+
+A cumulative song: every verse adds that day's gift and then repeats all the ones before it,
+counting back down to the first. The gifts are just a list, so any other song built the same way
+works too, with its gifts loaded from a file. It's sung by the same rules as the cumulative songs
+in `songs.rs`, just with gifts that don't have to be known at compile time.
+
+*/
+
+/// The real song, one gift per day. Later days get their count in front ("two turtle doves");
+/// the first day's gift is sung as written. `TwelveDays` sings other gifts the same way.
+pub const SONG: Song = Song {
+    name: "twelve-days",
+    title: "The Twelve Days of Christmas",
+    verse: "On the {ordinal} day of Christmas my true love gave to me\n{lines}.",
+    verses: Verses::Cumulative {
+        items: &[
+            &["a partridge in a pear tree"],
+            &["turtle doves"],
+            &["French hens"],
+            &["calling birds"],
+            &["gold rings"],
+            &["geese a-laying"],
+            &["swans a-swimming"],
+            &["maids a-milking"],
+            &["ladies dancing"],
+            &["lords a-leaping"],
+            &["pipers piping"],
+            &["drummers drumming"],
+        ],
+        line: "{cardinal} {0}",
+        first: "{0}",
+        and: "and ",
+    },
+};
+
+/// A cumulative song with one gift per day
+#[derive(Debug, Clone)]
+pub struct TwelveDays {
+    verse: String,
+    gifts: Vec<String>,
+}
+
+impl Default for TwelveDays {
+    fn default() -> Self {
+        let Verses::Cumulative { items, .. } = SONG.verses else { unreachable!("SONG is cumulative") };
+
+        TwelveDays::new(items.iter().map(|item| item[0].to_string()).collect())
+    }
+}
+
+impl TwelveDays {
+    /// A song with one verse per gift
+    pub fn new(gifts: Vec<String>) -> Self {
+        TwelveDays {
+            verse: SONG.verse.to_string(),
+            gifts,
+        }
+    }
+
+    /// Gifts from a file, one per line. Blank lines and lines starting with `#` are skipped.
+    pub fn load(path: impl AsRef<Path>) -> io::Result<Self> {
+        let contents = fs::read_to_string(path)?;
+
+        let gifts: Vec<String> = contents
+            .lines()
+            .map(str::trim)
+            .filter(|line| !line.is_empty() && !line.starts_with('#'))
+            .map(String::from)
+            .collect();
+
+        if gifts.is_empty() {
+            return Err(io::Error::new(io::ErrorKind::InvalidData, "a song needs at least one gift"));
+        }
+
+        Ok(TwelveDays::new(gifts))
+    }
+
+    /// Use a different first line, with `{ordinal}` where "first", "second", ... goes
+    pub fn with_opening(mut self, opening: &str) -> Self {
+        self.verse = format!("{opening}\n{{lines}}.");
+        self
+    }
+
+    pub fn days(&self) -> usize {
+        self.gifts.len()
+    }
+
+    /// The verse for `day`, counting from 1
+    pub fn verse(&self, day: usize) -> String {
+        let Verses::Cumulative { line, first, and, .. } = SONG.verses else { unreachable!("SONG is cumulative") };
+        let items: Vec<[&str; 1]> = self.gifts.iter().map(|gift| [gift.as_str()]).collect();
+        let items: Vec<&[&str]> = items.iter().map(|item| &item[..]).collect();
+
+        songs::capitalize_lines(&songs::cumulative_verse(&self.verse, &items, day, line, first, and))
+    }
+
+    /// The whole song, verses separated by blank lines
+    pub fn lyrics(&self) -> String {
+        (1..=self.days())
+            .map(|day| self.verse(day))
+            .collect::<Vec<_>>()
+            .join("\n\n")
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const DAY_12: &str = "\
+On the twelfth day of Christmas my true love gave to me
+Twelve drummers drumming,
+Eleven pipers piping,
+Ten lords a-leaping,
+Nine ladies dancing,
+Eight maids a-milking,
+Seven swans a-swimming,
+Six geese a-laying,
+Five gold rings,
+Four calling birds,
+Three French hens,
+Two turtle doves,
+And a partridge in a pear tree.";
+
+    #[test]
+    fn the_real_lyrics() {
+        let song = TwelveDays::default();
+
+        assert_eq!(song.days(), 12);
+        assert_eq!(song.verse(1), "On the first day of Christmas my true love gave to me\nA partridge in a pear tree.");
+        assert_eq!(song.verse(2), "On the second day of Christmas my true love gave to me\nTwo turtle doves,\nAnd a partridge in a pear tree.");
+        assert_eq!(song.verse(12), DAY_12);
+
+        let lyrics = song.lyrics();
+        assert!(lyrics.starts_with("On the first day of Christmas my true love gave to me\nA partridge in a pear tree.\n\nOn the second day"));
+        assert!(lyrics.ends_with(&format!("\n\n{DAY_12}")));
+        assert_eq!(lyrics.matches("On the ").count(), 12);
+    }
+
+    #[test]
+    fn the_songs_entry_is_the_same_song() {
+        let song = songs::by_name("twelve-days").unwrap();
+
+        assert_eq!(song.verse(12), DAY_12);
+        assert_eq!(song.lyrics(), TwelveDays::default().lyrics());
+    }
+
+    #[test]
+    fn other_gifts_and_openings() {
+        let song = TwelveDays::new(vec!["a cup of tea".into(), "biscuits".into()])
+            .with_opening("On the {ordinal} day of term my flatmate gave to me");
+
+        assert_eq!(song.verse(1), "On the first day of term my flatmate gave to me\nA cup of tea.");
+        assert_eq!(song.verse(2), "On the second day of term my flatmate gave to me\nTwo biscuits,\nAnd a cup of tea.");
+    }
+}
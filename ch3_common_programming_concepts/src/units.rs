@@ -0,0 +1,439 @@
+use std::error::Error;
+use std::fmt;
+use std::str::FromStr;
+
+/* Units
+
+The chapter's suggested exercise, converting temperatures between Fahrenheit and Celsius, plus
+lengths and masses while we're at it.
+
+Temperatures get a newtype per scale, so a `Celsius` can't be passed where a `Fahrenheit` is
+wanted, and `From` converts between them. None of them can go below absolute zero. Lengths and
+masses are a value with a unit, and can't be negative.
+
+`Quantity` is any of them, parsed from text like "100C", "5 km" or "12oz", which is what the
+`convert` binary reads.
+
+*/
+
+/// 0 K in °C
+pub const ABSOLUTE_ZERO_C: f64 = -273.15;
+
+/// Why a quantity can't be made or converted
+#[derive(Debug, Clone, PartialEq)]
+pub enum UnitError {
+    /// Not a number (or NaN/infinite)
+    InvalidNumber(String),
+    UnknownUnit(String),
+    BelowAbsoluteZero(Quantity),
+    /// A negative length or mass
+    Negative(Quantity),
+    /// Converting between units of different things, like meters to kilograms
+    Incompatible { from: Unit, to: Unit },
+}
+
+impl fmt::Display for UnitError {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        match self {
+            UnitError::InvalidNumber(input)      => write!(f, "\"{input}\" isn't a number"),
+            UnitError::UnknownUnit(input)        => write!(f, "\"{input}\" isn't a unit I know"),
+            UnitError::BelowAbsoluteZero(q)      => write!(f, "{q} is below absolute zero"),
+            UnitError::Negative(q)               => write!(f, "{q} is negative, which a {} can't be", q.unit.dimension()),
+            UnitError::Incompatible { from, to } => {
+                write!(f, "can't convert {from} (a {}) to {to} (a {})", from.dimension(), to.dimension())
+            }
+        }
+    }
+}
+
+impl Error for UnitError {}
+
+// Reject NaN and infinity, which aren't amounts of anything
+fn finite(value: f64) -> Result<f64, UnitError> {
+    if value.is_finite() {
+        Ok(value)
+    } else {
+        Err(UnitError::InvalidNumber(value.to_string()))
+    }
+}
+
+macro_rules! temperature {
+    ($name:ident, $unit:expr, $doc:literal) => {
+        #[doc = $doc]
+        #[derive(Debug, Clone, Copy, PartialEq, PartialOrd)]
+        pub struct $name(f64);
+
+        impl $name {
+            /// Fails below absolute zero
+            pub fn new(degrees: f64) -> Result<Self, UnitError> {
+                let temperature = $name(finite(degrees)?);
+
+                // Rounding can leave absolute zero a hair below 0 K, so allow for that
+                if Kelvin::from(temperature).0 < -1e-9 {
+                    return Err(UnitError::BelowAbsoluteZero(Quantity::new(degrees, Unit::Temperature($unit))));
+                }
+
+                Ok(temperature)
+            }
+
+            pub fn degrees(&self) -> f64 {
+                self.0
+            }
+        }
+
+        impl fmt::Display for $name {
+            fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+                write!(f, "{}", Quantity::new(self.0, Unit::Temperature($unit)))
+            }
+        }
+    };
+}
+
+temperature!(Celsius, TemperatureUnit::Celsius, "Degrees Celsius");
+temperature!(Fahrenheit, TemperatureUnit::Fahrenheit, "Degrees Fahrenheit");
+temperature!(Kelvin, TemperatureUnit::Kelvin, "Kelvin");
+
+impl From<Celsius> for Kelvin {
+    fn from(c: Celsius) -> Self {
+        Kelvin(c.0 - ABSOLUTE_ZERO_C)
+    }
+}
+
+impl From<Kelvin> for Celsius {
+    fn from(k: Kelvin) -> Self {
+        Celsius(k.0 + ABSOLUTE_ZERO_C)
+    }
+}
+
+impl From<Celsius> for Fahrenheit {
+    fn from(c: Celsius) -> Self {
+        Fahrenheit(c.0 * 9.0 / 5.0 + 32.0)
+    }
+}
+
+impl From<Fahrenheit> for Celsius {
+    fn from(f: Fahrenheit) -> Self {
+        Celsius((f.0 - 32.0) * 5.0 / 9.0)
+    }
+}
+
+impl From<Fahrenheit> for Kelvin {
+    fn from(f: Fahrenheit) -> Self {
+        Kelvin::from(Celsius::from(f))
+    }
+}
+
+impl From<Kelvin> for Fahrenheit {
+    fn from(k: Kelvin) -> Self {
+        Fahrenheit::from(Celsius::from(k))
+    }
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub enum TemperatureUnit {
+    Celsius,
+    Fahrenheit,
+    Kelvin,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub enum LengthUnit {
+    Millimeter,
+    Centimeter,
+    Meter,
+    Kilometer,
+    Inch,
+    Foot,
+    Yard,
+    Mile,
+}
+
+impl LengthUnit {
+    /// How many meters one of these is
+    pub fn meters(&self) -> f64 {
+        match self {
+            LengthUnit::Millimeter => 0.001,
+            LengthUnit::Centimeter => 0.01,
+            LengthUnit::Meter      => 1.0,
+            LengthUnit::Kilometer  => 1000.0,
+            LengthUnit::Inch       => 0.0254,
+            LengthUnit::Foot       => 0.3048,
+            LengthUnit::Yard       => 0.9144,
+            LengthUnit::Mile       => 1609.344,
+        }
+    }
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub enum MassUnit {
+    Milligram,
+    Gram,
+    Kilogram,
+    Tonne,
+    Ounce,
+    Pound,
+    Stone,
+}
+
+impl MassUnit {
+    /// How many grams one of these is
+    pub fn grams(&self) -> f64 {
+        match self {
+            MassUnit::Milligram => 0.001,
+            MassUnit::Gram      => 1.0,
+            MassUnit::Kilogram  => 1000.0,
+            MassUnit::Tonne     => 1_000_000.0,
+            MassUnit::Ounce     => 28.349_523_125,
+            MassUnit::Pound     => 453.592_37,
+            MassUnit::Stone     => 6_350.293_18,
+        }
+    }
+}
+
+/// A unit of anything `Quantity` can measure
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub enum Unit {
+    Temperature(TemperatureUnit),
+    Length(LengthUnit),
+    Mass(MassUnit),
+}
+
+impl Unit {
+    /// Every unit, with the symbols it's written as. The first symbol is the one it's shown as.
+    pub const ALL: [(Unit, &'static [&'static str]); 18] = [
+        (Unit::Temperature(TemperatureUnit::Celsius), &["°C", "C", "celsius"]),
+        (Unit::Temperature(TemperatureUnit::Fahrenheit), &["°F", "F", "fahrenheit"]),
+        (Unit::Temperature(TemperatureUnit::Kelvin), &["K", "kelvin"]),
+        (Unit::Length(LengthUnit::Millimeter), &["mm", "millimeters", "millimetres"]),
+        (Unit::Length(LengthUnit::Centimeter), &["cm", "centimeters", "centimetres"]),
+        (Unit::Length(LengthUnit::Meter), &["m", "meters", "metres"]),
+        (Unit::Length(LengthUnit::Kilometer), &["km", "kilometers", "kilometres"]),
+        (Unit::Length(LengthUnit::Inch), &["in", "inches", "\""]),
+        (Unit::Length(LengthUnit::Foot), &["ft", "feet", "'"]),
+        (Unit::Length(LengthUnit::Yard), &["yd", "yards"]),
+        (Unit::Length(LengthUnit::Mile), &["mi", "miles"]),
+        (Unit::Mass(MassUnit::Milligram), &["mg", "milligrams"]),
+        (Unit::Mass(MassUnit::Gram), &["g", "grams"]),
+        (Unit::Mass(MassUnit::Kilogram), &["kg", "kilograms"]),
+        (Unit::Mass(MassUnit::Tonne), &["t", "tonnes"]),
+        (Unit::Mass(MassUnit::Ounce), &["oz", "ounces"]),
+        (Unit::Mass(MassUnit::Pound), &["lb", "lbs", "pounds"]),
+        (Unit::Mass(MassUnit::Stone), &["st", "stone"]),
+    ];
+
+    /// What kind of thing the unit measures
+    pub fn dimension(&self) -> &'static str {
+        match self {
+            Unit::Temperature(_) => "temperature",
+            Unit::Length(_)      => "length",
+            Unit::Mass(_)        => "mass",
+        }
+    }
+
+    fn symbols(&self) -> &'static [&'static str] {
+        Unit::ALL.iter().find(|(unit, _)| unit == self).map(|(_, symbols)| *symbols).unwrap_or(&[])
+    }
+}
+
+impl FromStr for Unit {
+    type Err = UnitError;
+
+    // Short symbols are case-sensitive (mm isn't Mm), but names and C/F/K aren't
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        let s = s.trim();
+
+        let matches = |symbol: &&str| {
+            *symbol == s || (symbol.len() > 2 || matches!(*symbol, "C" | "F" | "K")) && symbol.eq_ignore_ascii_case(s)
+        };
+
+        Unit::ALL
+            .iter()
+            .find(|(_, symbols)| symbols.iter().any(matches))
+            .map(|(unit, _)| *unit)
+            .ok_or_else(|| UnitError::UnknownUnit(s.to_string()))
+    }
+}
+
+impl fmt::Display for Unit {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        write!(f, "{}", self.symbols().first().unwrap_or(&"?"))
+    }
+}
+
+/// An amount of something, in some unit
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct Quantity {
+    pub value: f64,
+    pub unit: Unit,
+}
+
+impl Quantity {
+    // Not checked, for building error messages about invalid quantities
+    fn new(value: f64, unit: Unit) -> Self {
+        Quantity { value, unit }
+    }
+
+    /// Fails for quantities that can't exist: below absolute zero, or a negative length or mass
+    pub fn checked(value: f64, unit: Unit) -> Result<Self, UnitError> {
+        let quantity = Quantity::new(finite(value)?, unit);
+
+        match unit {
+            Unit::Temperature(TemperatureUnit::Celsius)    => Celsius::new(value).map(|_| quantity),
+            Unit::Temperature(TemperatureUnit::Fahrenheit) => Fahrenheit::new(value).map(|_| quantity),
+            Unit::Temperature(TemperatureUnit::Kelvin)     => Kelvin::new(value).map(|_| quantity),
+            Unit::Length(_) | Unit::Mass(_) if value < 0.0 => Err(UnitError::Negative(quantity)),
+            Unit::Length(_) | Unit::Mass(_)                => Ok(quantity),
+        }
+    }
+
+    /// The same amount in another unit of the same kind
+    pub fn convert(&self, to: Unit) -> Result<Quantity, UnitError> {
+        let value = match (self.unit, to) {
+            (Unit::Temperature(from), Unit::Temperature(to)) => {
+                let kelvin = match from {
+                    TemperatureUnit::Celsius    => Kelvin::from(Celsius(self.value)),
+                    TemperatureUnit::Fahrenheit => Kelvin::from(Fahrenheit(self.value)),
+                    TemperatureUnit::Kelvin     => Kelvin(self.value),
+                };
+
+                match to {
+                    TemperatureUnit::Celsius    => Celsius::from(kelvin).0,
+                    TemperatureUnit::Fahrenheit => Fahrenheit::from(kelvin).0,
+                    TemperatureUnit::Kelvin     => kelvin.0,
+                }
+            }
+            (Unit::Length(from), Unit::Length(to)) => self.value * from.meters() / to.meters(),
+            (Unit::Mass(from), Unit::Mass(to))     => self.value * from.grams() / to.grams(),
+            (from, to)                             => return Err(UnitError::Incompatible { from, to }),
+        };
+
+        Ok(Quantity::new(value, to))
+    }
+}
+
+// How much of the start of `s` is a number: a sign, digits and a point, then maybe an exponent.
+// An `e` only starts an exponent when digits follow it, so it could still start a unit.
+fn number_len(s: &str) -> usize {
+    let bytes = s.as_bytes();
+    let digits = |from: usize| bytes[from..].iter().take_while(|&&b| b.is_ascii_digit() || b == b'.').count();
+
+    let sign = usize::from(matches!(bytes.first(), Some(b'-' | b'+')));
+    let mantissa = sign + digits(sign);
+
+    let exponent = match bytes.get(mantissa) {
+        Some(b'e' | b'E') => {
+            let sign = usize::from(matches!(bytes.get(mantissa + 1), Some(b'-' | b'+')));
+            let power = bytes[mantissa + 1 + sign..].iter().take_while(|b| b.is_ascii_digit()).count();
+
+            if power > 0 { 1 + sign + power } else { 0 }
+        }
+        _ => 0,
+    };
+
+    mantissa + exponent
+}
+
+// Two decimal places at most, and none that are just zeros
+fn trim_zeros(number: &str) -> &str {
+    let number = if number.contains('.') { number.trim_end_matches('0').trim_end_matches('.') } else { number };
+
+    if number == "-0" { "0" } else { number }
+}
+
+impl FromStr for Quantity {
+    type Err = UnitError;
+
+    /// A number followed by a unit, with or without a space: "100C", "5 km", "-40 °F", "1e3 m"
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        let s = s.trim();
+        let (number, unit) = s.split_at(number_len(s));
+
+        // With no number at all, the whole thing is what isn't a number
+        let value: f64 = number.parse().map_err(|_| {
+            UnitError::InvalidNumber(if number.is_empty() { s } else { number }.to_string())
+        })?;
+        let unit: Unit = unit.parse()?;
+
+        Quantity::checked(value, unit)
+    }
+}
+
+impl fmt::Display for Quantity {
+    // Two decimal places at most, and none that are just zeros. Amounts too small for that to show
+    // (or too big to want every digit) get three significant digits and an exponent: "1e-6 kg"
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        let magnitude = self.value.abs();
+
+        let value = if magnitude != 0.0 && !(0.01..1e15).contains(&magnitude) {
+            let value = format!("{:.2e}", self.value);
+            let (mantissa, exponent) = value.split_once('e').unwrap_or((&value, "0"));
+
+            format!("{}e{exponent}", trim_zeros(mantissa))
+        } else {
+            trim_zeros(&format!("{:.2}", self.value)).to_string()
+        };
+
+        match self.unit {
+            Unit::Temperature(TemperatureUnit::Celsius | TemperatureUnit::Fahrenheit) => write!(f, "{value}{}", self.unit),
+            _                                                                       => write!(f, "{value} {}", self.unit),
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn quantity(s: &str) -> Quantity {
+        s.parse().unwrap()
+    }
+
+    fn converted(s: &str, to: &str) -> String {
+        quantity(s).convert(to.parse().unwrap()).unwrap().to_string()
+    }
+
+    #[test]
+    fn shows_at_most_two_decimal_places() {
+        assert_eq!(quantity("100C").to_string(), "100°C");
+        assert_eq!(converted("5 km", "mi"), "3.11 mi");
+        assert_eq!(converted("1 kg", "lb"), "2.2 lb");
+        assert_eq!(converted("0 m", "ft"), "0 ft");
+    }
+
+    #[test]
+    fn small_and_huge_amounts_get_an_exponent() {
+        assert_eq!(converted("1mg", "kg"), "1e-6 kg");
+        assert_eq!(converted("1 in", "km"), "2.54e-5 km");
+        assert_eq!(quantity("0.004 m").to_string(), "4e-3 m");
+        assert_eq!(quantity("-0.001C").to_string(), "-1e-3°C");
+        assert_eq!(quantity("1e20 mm").to_string(), "1e20 mm");
+    }
+
+    #[test]
+    fn shown_amounts_parse_back() {
+        for shown in ["1e-6 kg", "2.54e-5 km", "-1e-3°C", "1e20 mm"] {
+            assert_eq!(quantity(shown).to_string(), shown);
+        }
+    }
+
+    #[test]
+    fn numbers_can_have_exponents() {
+        assert_eq!(quantity("1e3 m"), Quantity::new(1000.0, Unit::Length(LengthUnit::Meter)));
+        assert_eq!(quantity("1E-3kg"), Quantity::new(0.001, Unit::Mass(MassUnit::Kilogram)));
+        assert_eq!(quantity("2.5e+2C"), Quantity::new(250.0, Unit::Temperature(TemperatureUnit::Celsius)));
+    }
+
+    #[test]
+    fn an_e_without_digits_is_part_of_the_unit() {
+        assert_eq!("1e m".parse::<Quantity>(), Err(UnitError::UnknownUnit("e m".to_string())));
+        assert_eq!("1e- m".parse::<Quantity>(), Err(UnitError::UnknownUnit("e- m".to_string())));
+    }
+
+    #[test]
+    fn rejects_what_cant_be_a_quantity() {
+        assert_eq!("km".parse::<Quantity>(), Err(UnitError::InvalidNumber("km".to_string())));
+        assert!(matches!("-300C".parse::<Quantity>(), Err(UnitError::BelowAbsoluteZero(_))));
+        assert!(matches!("-1 m".parse::<Quantity>(), Err(UnitError::Negative(_))));
+        assert!(matches!(quantity("1 m").convert(Unit::Mass(MassUnit::Gram)), Err(UnitError::Incompatible { .. })));
+    }
+}
@@ -0,0 +1,296 @@
+use crate::tracked::Log;
+
+/* Demos
+
+The scenes from the chapter's figures (`img/trpl04-01.svg` to `img/trpl04-04.svg`), played out
+with `Tracked` values so the log shows who owns which heap data, and when it's freed.
+
+*/
+
+/// One figure's code, and a function that runs it and returns the log
+#[derive(Debug, Clone, Copy)]
+pub struct Demo {
+    pub figure: &'static str,
+    pub title: &'static str,
+    pub code: &'static str,
+    pub run: fn() -> Log,
+}
+
+pub const DEMOS: [Demo; 4] = [
+    Demo {
+        figure: "trpl04-01",
+        title: "A String in memory",
+        code: "let s1 = String::from(\"hello\");",
+        run: string_in_memory,
+    },
+    Demo {
+        figure: "trpl04-02",
+        title: "Moving s1 to s2 copies the pointer, length and capacity, not the heap data",
+        code: "let s1 = String::from(\"hello\");\nlet s2 = s1;",
+        run: move_shares_heap_data,
+    },
+    Demo {
+        figure: "trpl04-03",
+        title: "Cloning s1 to s2 copies the heap data too",
+        code: "let s1 = String::from(\"hello\");\nlet s2 = s1.clone();",
+        run: clone_copies_heap_data,
+    },
+    Demo {
+        figure: "trpl04-04",
+        title: "s1 is no longer valid after the move, so only s2 frees the heap data",
+        code: "let s1 = String::from(\"hello\");\n{\n    let s2 = s1;\n}",
+        run: moved_value_is_not_freed,
+    },
+];
+
+/// Look a demo up by its figure, e.g. "trpl04-03" (or just "3")
+pub fn by_figure(figure: &str) -> Option<&'static Demo> {
+    DEMOS.iter().find(|demo| demo.figure == figure || demo.figure.ends_with(&format!("-0{figure}")))
+}
+
+// trpl04-01: one owner, one lot of heap data, freed when the owner goes out of scope
+fn string_in_memory() -> Log {
+    let log = Log::new();
+    let s1 = log.track("s1", String::from("hello"));
+
+    {
+        // The part of a String that lives on the stack
+        let r1 = s1.borrow("r1");
+        log.note(format!("s1 has ptr {:p}, len {}, capacity {}", r1.as_ptr(), r1.len(), r1.capacity()));
+    }
+
+    drop(s1);
+    log
+}
+
+// trpl04-02: after the move, s2 reaches the same heap data s1 did
+fn move_shares_heap_data() -> Log {
+    let log = Log::new();
+    let s1 = log.track("s1", String::from("hello"));
+    let before = s1.borrow("r1").as_ptr();
+
+    let s2 = s1.move_to("s2");
+    let after = s2.borrow("r2").as_ptr();
+
+    log.note(format!("s1 pointed at {before:p}, s2 points at {after:p}: the same heap data"));
+
+    drop(s2);
+    log
+}
+
+// trpl04-03: two owners, two lots of heap data, each freed once
+fn clone_copies_heap_data() -> Log {
+    let log = Log::new();
+    let s1 = log.track("s1", String::from("hello"));
+    let mut s2 = s1.clone_to("s2");
+
+    log.note(format!("s1 points at {:p}, s2 points at {:p}: two copies", s1.borrow("r1").as_ptr(), s2.borrow("r2").as_ptr()));
+
+    // Changing the copy leaves the original alone
+    s2.borrow_mut("r3").push_str(", world");
+    log.note(format!("s1 is {:?}, s2 is {:?}", *s1.borrow("r4"), *s2.borrow("r5")));
+
+    drop(s2);
+    drop(s1);
+    log
+}
+
+// trpl04-04: s1's scope lasts longer than s2's, but s1 was moved, so there's nothing left for it to free
+fn moved_value_is_not_freed() -> Log {
+    let log = Log::new();
+    let s1 = log.track("s1", String::from("hello"));
+
+    {
+        let _s2 = s1.move_to("s2");
+    }
+
+    log.note("s1 goes out of scope here, but doesn't own anything any more");
+    log
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::tracked::Event;
+
+    fn created(name: &str, data: usize) -> Event {
+        Event::Created { name: name.to_string(), data, value: String::from("\"hello\"") }
+    }
+
+    fn moved(from: &str, to: &str, data: usize) -> Event {
+        Event::Moved { from: from.to_string(), to: to.to_string(), data }
+    }
+
+    fn borrowed(owner: &str, by: &str, mutable: bool) -> Event {
+        Event::Borrowed { owner: owner.to_string(), by: by.to_string(), mutable }
+    }
+
+    fn ended(owner: &str, by: &str, mutable: bool) -> Event {
+        Event::BorrowEnded { owner: owner.to_string(), by: by.to_string(), mutable }
+    }
+
+    fn dropped(name: &str, data: usize) -> Event {
+        Event::Dropped { name: name.to_string(), data }
+    }
+
+    fn note(start: &str) -> Event {
+        Event::Note(start.to_string())
+    }
+
+    // Notes with pointers in them change from run to run, so an expected note only has to match
+    // the start of the real one
+    fn assert_events(figure: &str, expected: &[Event]) -> Log {
+        let log = (by_figure(figure).unwrap().run)();
+        let events = log.events();
+
+        assert_eq!(events.len(), expected.len(), "{figure}:\n{log}");
+
+        for (i, (event, expected)) in events.iter().zip(expected).enumerate() {
+            match (event, expected) {
+                (Event::Note(note), Event::Note(start)) => assert!(note.starts_with(start.as_str()), "{figure} event {}: {note}", i + 1),
+                _                                       => assert_eq!(event, expected, "{figure} event {}", i + 1),
+            }
+        }
+
+        log
+    }
+
+    // The two addresses in a note like "s1 pointed at 0x..., s2 points at 0x...: ..."
+    fn addresses(note: &str) -> Vec<&str> {
+        note.split([' ', ',', ':']).filter(|word| word.starts_with("0x")).collect()
+    }
+
+    fn notes(log: &Log) -> Vec<String> {
+        log.events().into_iter().filter_map(|event| match event {
+            Event::Note(note) => Some(note),
+            _                 => None,
+        }).collect()
+    }
+
+    #[test]
+    fn a_string_in_memory() {
+        let log = assert_events("trpl04-01", &[
+            created("s1", 1),
+            borrowed("s1", "r1", false),
+            note("s1 has ptr 0x"),
+            ended("s1", "r1", false),
+            dropped("s1", 1),
+        ]);
+
+        assert!(notes(&log)[0].ends_with(", len 5, capacity 5"));
+        assert_eq!(log.frees(1), 1);
+    }
+
+    #[test]
+    fn moving_shares_heap_data() {
+        let log = assert_events("trpl04-02", &[
+            created("s1", 1),
+            borrowed("s1", "r1", false),
+            ended("s1", "r1", false),
+            moved("s1", "s2", 1),
+            borrowed("s2", "r2", false),
+            ended("s2", "r2", false),
+            note("s1 pointed at 0x"),
+            dropped("s2", 1),
+        ]);
+
+        let note = &notes(&log)[0];
+        let addresses = addresses(note);
+        assert_eq!(addresses.len(), 2, "{note}");
+        assert_eq!(addresses[0], addresses[1], "{note}");
+        assert_eq!(log.frees(1), 1);
+    }
+
+    #[test]
+    fn cloning_copies_heap_data() {
+        let log = assert_events("trpl04-03", &[
+            created("s1", 1),
+            Event::Cloned { from: String::from("s1"), to: String::from("s2"), data: 1, copy: 2 },
+            borrowed("s1", "r1", false),
+            borrowed("s2", "r2", false),
+            ended("s2", "r2", false),
+            ended("s1", "r1", false),
+            note("s1 points at 0x"),
+            borrowed("s2", "r3", true),
+            ended("s2", "r3", true),
+            borrowed("s1", "r4", false),
+            borrowed("s2", "r5", false),
+            ended("s2", "r5", false),
+            ended("s1", "r4", false),
+            note("s1 is \"hello\", s2 is \"hello, world\""),
+            dropped("s2", 2),
+            dropped("s1", 1),
+        ]);
+
+        let note = &notes(&log)[0];
+        let addresses = addresses(note);
+        assert_eq!(addresses.len(), 2, "{note}");
+        assert_ne!(addresses[0], addresses[1], "{note}");
+        assert_eq!((log.frees(1), log.frees(2)), (1, 1));
+    }
+
+    #[test]
+    fn a_moved_value_is_not_freed() {
+        let log = assert_events("trpl04-04", &[
+            created("s1", 1),
+            moved("s1", "s2", 1),
+            dropped("s2", 1),
+            note("s1 goes out of scope here, but doesn't own anything any more"),
+        ]);
+
+        assert!(!log.events().contains(&dropped("s1", 1)));
+        assert_eq!(log.frees(1), 1);
+    }
+
+    #[test]
+    fn every_borrow_ends_and_nothing_is_dropped_while_borrowed() {
+        for demo in &DEMOS {
+            // Borrows still going: (owner, by, mutable)
+            let mut open: Vec<(String, String, bool)> = Vec::new();
+
+            for event in (demo.run)().events() {
+                match event {
+                    Event::Borrowed { owner, by, mutable } => {
+                        assert!(
+                            !open.iter().any(|(o, _, m)| *o == owner && (*m || mutable)),
+                            "{}: {by} borrows {owner} while it's already borrowed", demo.figure,
+                        );
+                        open.push((owner, by, mutable));
+                    }
+                    Event::BorrowEnded { owner, by, mutable } => {
+                        let Some(i) = open.iter().position(|borrow| *borrow == (owner.clone(), by.clone(), mutable)) else {
+                            panic!("{}: {by} stops borrowing {owner} without having started", demo.figure);
+                        };
+                        open.remove(i);
+                    }
+                    Event::Moved { from: name, .. } | Event::Dropped { name, .. } => {
+                        assert!(!open.iter().any(|(owner, ..)| *owner == name), "{}: {name} moved or dropped while borrowed", demo.figure);
+                    }
+                    _ => {}
+                }
+            }
+
+            assert!(open.is_empty(), "{}: borrows that never ended: {open:?}", demo.figure);
+        }
+    }
+
+    #[test]
+    fn all_heap_data_is_freed_exactly_once() {
+        for demo in &DEMOS {
+            let log = (demo.run)();
+
+            for event in log.events() {
+                if let Event::Created { data, .. } | Event::Cloned { copy: data, .. } = event {
+                    assert_eq!(log.frees(data), 1, "{}: data #{data}", demo.figure);
+                }
+            }
+        }
+    }
+
+    #[test]
+    fn figures_can_be_looked_up_by_number() {
+        assert_eq!(by_figure("3").map(|demo| demo.figure), Some("trpl04-03"));
+        assert_eq!(by_figure("trpl04-04").map(|demo| demo.figure), Some("trpl04-04"));
+        assert!(by_figure("5").is_none());
+    }
+}
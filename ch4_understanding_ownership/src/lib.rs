@@ -0,0 +1,13 @@
+/* Understanding Ownership
+
+Ownership is checked at compile time and leaves no trace at runtime. `Tracked` puts a trace back,
+logging moves, clones, borrows and drops as they happen, and `demos` uses it to act out the
+chapter's figures.
+
+*/
+
+pub mod demos;
+pub mod tracked;
+
+pub use demos::Demo;
+pub use tracked::{Event, Log, Tracked};
@@ -1,3 +1,29 @@
+use std::env;
+use std::process;
+
+use ch4_understanding_ownership::demos::{self, Demo, DEMOS};
+
+fn show(demo: &Demo) {
+    println!("{} ({})\n", demo.title, demo.figure);
+
+    for line in demo.code.lines() {
+        println!("    {line}");
+    }
+
+    println!("\n{}", (demo.run)());
+}
+
 fn main() {
-    println!("Hello, world!");
+
+    // Either the figure asked for (`cargo run -- 3`), or all of them
+    match env::args().nth(1) {
+        Some(figure) => match demos::by_figure(&figure) {
+            Some(demo) => show(demo),
+            None       => {
+                eprintln!("No figure {figure}, try 1 to {}", DEMOS.len());
+                process::exit(1);
+            }
+        },
+        None => DEMOS.iter().for_each(show),
+    }
 }
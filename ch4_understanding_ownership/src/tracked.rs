@@ -0,0 +1,243 @@
+use std::cell::RefCell;
+use std::fmt;
+use std::ops::{Deref, DerefMut};
+use std::rc::Rc;
+
+/* Tracked Values
+
+The compiler checks the ownership rules and then they vanish from the program, which makes them
+hard to watch. `Tracked<T>` wraps a value and writes down what happens to it in a `Log`:
+
+    let log = Log::new();
+    let s1 = log.track("s1", String::from("hello"));
+    let s2 = s1.move_to("s2");
+    drop(s2);
+
+    // s1 owns "hello" (data #1)
+    // s1 moved to s2, data #1 isn't copied
+    // s2 dropped, freeing data #1
+
+Moves and clones have to be spelled out (`move_to`, `clone_to`), since a plain `let s2 = s1;`
+can't be noticed by the value being moved. Drops are noticed by `Drop`, like `String` freeing its
+heap memory. Each value's "heap data" gets a number, so it's clear when two variables share it
+(a move) and when there are two copies of it (a clone).
+
+*/
+
+/// Something that happened to a tracked value
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum Event {
+    /// A new value, with new heap data
+    Created { name: String, data: usize, value: String },
+    /// Ownership passed from one variable to another, the heap data stays where it is
+    Moved { from: String, to: String, data: usize },
+    /// The heap data was copied, so there are two of it
+    Cloned { from: String, to: String, data: usize, copy: usize },
+    Borrowed { owner: String, by: String, mutable: bool },
+    BorrowEnded { owner: String, by: String, mutable: bool },
+    /// The owner went out of scope, and the heap data was freed
+    Dropped { name: String, data: usize },
+    /// Anything else worth writing down along the way
+    Note(String),
+}
+
+impl fmt::Display for Event {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        let mutably = |mutable: &bool| if *mutable { " mutably" } else { "" };
+
+        match self {
+            Event::Created { name, data, value }      => write!(f, "{name} owns {value} (data #{data})"),
+            Event::Moved { from, to, data }           => write!(f, "{from} moved to {to}, data #{data} isn't copied"),
+            Event::Cloned { from, to, data, copy }    => write!(f, "{from} cloned to {to}, data #{data} copied to data #{copy}"),
+            Event::Borrowed { owner, by, mutable }    => write!(f, "{by} borrows {owner}{}", mutably(mutable)),
+            Event::BorrowEnded { owner, by, mutable } => write!(f, "{by} stops borrowing {owner}{}", mutably(mutable)),
+            Event::Dropped { name, data }             => write!(f, "{name} dropped, freeing data #{data}"),
+            Event::Note(note)                         => write!(f, "({note})"),
+        }
+    }
+}
+
+#[derive(Debug, Default)]
+struct Events {
+    events: Vec<Event>,
+    next_data: usize,
+}
+
+/// Where tracked values write down what happens to them. Clones of a log share the same events.
+#[derive(Debug, Clone, Default)]
+pub struct Log {
+    inner: Rc<RefCell<Events>>,
+}
+
+impl Log {
+    pub fn new() -> Self {
+        Log::default()
+    }
+
+    /// Start tracking `value`, owned by a variable called `name`
+    pub fn track<T: fmt::Debug>(&self, name: &str, value: T) -> Tracked<T> {
+        let data = self.allocate();
+
+        self.push(Event::Created { name: name.to_string(), data, value: format!("{value:?}") });
+
+        Tracked { name: name.to_string(), data, value: Some(value), log: self.clone() }
+    }
+
+    /// Write down something that isn't an event of its own
+    pub fn note(&self, note: impl Into<String>) {
+        self.push(Event::Note(note.into()));
+    }
+
+    /// Everything so far, oldest first
+    pub fn events(&self) -> Vec<Event> {
+        self.inner.borrow().events.clone()
+    }
+
+    /// How many times the heap data numbered `data` was freed: 0 while it's still owned, and
+    /// never more than 1 (that would be a double free)
+    pub fn frees(&self, data: usize) -> usize {
+        self.inner.borrow().events.iter()
+            .filter(|event| matches!(event, Event::Dropped { data: freed, .. } if *freed == data))
+            .count()
+    }
+
+    fn allocate(&self) -> usize {
+        let mut inner = self.inner.borrow_mut();
+        inner.next_data += 1;
+        inner.next_data
+    }
+
+    fn push(&self, event: Event) {
+        self.inner.borrow_mut().events.push(event);
+    }
+}
+
+impl fmt::Display for Log {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        for (i, event) in self.inner.borrow().events.iter().enumerate() {
+            writeln!(f, "{:>3}. {event}", i + 1)?;
+        }
+
+        Ok(())
+    }
+}
+
+/// A value that logs its moves, clones, borrows and drop
+#[derive(Debug)]
+pub struct Tracked<T> {
+    name: String,
+    data: usize,
+    // Only `None` in the husk left behind by `move_to`, which has nothing to drop
+    value: Option<T>,
+    log: Log,
+}
+
+impl<T> Tracked<T> {
+    /// The variable that owns the value
+    pub fn name(&self) -> &str {
+        &self.name
+    }
+
+    /// The number of the value's heap data
+    pub fn data(&self) -> usize {
+        self.data
+    }
+
+    /// `let to = self;`: the value changes owner, and the old one can't be used any more
+    pub fn move_to(mut self, to: &str) -> Tracked<T> {
+        self.log.push(Event::Moved { from: self.name.clone(), to: to.to_string(), data: self.data });
+
+        Tracked { name: to.to_string(), data: self.data, value: self.value.take(), log: self.log.clone() }
+    }
+
+    /// `let to = self.clone();`: a deep copy, with heap data of its own
+    pub fn clone_to(&self, to: &str) -> Tracked<T>
+    where
+        T: Clone,
+    {
+        let copy = self.log.allocate();
+
+        self.log.push(Event::Cloned { from: self.name.clone(), to: to.to_string(), data: self.data, copy });
+
+        Tracked { name: to.to_string(), data: copy, value: self.value.clone(), log: self.log.clone() }
+    }
+
+    /// `let by = &self;`, logged until the reference goes away
+    pub fn borrow(&self, by: &str) -> Borrow<'_, T> {
+        self.log.push(Event::Borrowed { owner: self.name.clone(), by: by.to_string(), mutable: false });
+
+        Borrow { owner: self, by: by.to_string() }
+    }
+
+    /// `let by = &mut self;`, logged until the reference goes away
+    pub fn borrow_mut(&mut self, by: &str) -> BorrowMut<'_, T> {
+        self.log.push(Event::Borrowed { owner: self.name.clone(), by: by.to_string(), mutable: true });
+
+        BorrowMut { owner: self, by: by.to_string() }
+    }
+
+    fn value(&self) -> &T {
+        self.value.as_ref().expect("moved-from values can't be reached")
+    }
+
+    fn value_mut(&mut self) -> &mut T {
+        self.value.as_mut().expect("moved-from values can't be reached")
+    }
+}
+
+impl<T> Drop for Tracked<T> {
+    fn drop(&mut self) {
+        // After a move, the new owner is responsible for freeing the data
+        if self.value.is_some() {
+            self.log.push(Event::Dropped { name: self.name.clone(), data: self.data });
+        }
+    }
+}
+
+/// A shared reference to a tracked value
+#[derive(Debug)]
+pub struct Borrow<'a, T> {
+    owner: &'a Tracked<T>,
+    by: String,
+}
+
+impl<T> Deref for Borrow<'_, T> {
+    type Target = T;
+
+    fn deref(&self) -> &T {
+        self.owner.value()
+    }
+}
+
+impl<T> Drop for Borrow<'_, T> {
+    fn drop(&mut self) {
+        self.owner.log.push(Event::BorrowEnded { owner: self.owner.name.clone(), by: self.by.clone(), mutable: false });
+    }
+}
+
+/// A mutable reference to a tracked value
+#[derive(Debug)]
+pub struct BorrowMut<'a, T> {
+    owner: &'a mut Tracked<T>,
+    by: String,
+}
+
+impl<T> Deref for BorrowMut<'_, T> {
+    type Target = T;
+
+    fn deref(&self) -> &T {
+        self.owner.value()
+    }
+}
+
+impl<T> DerefMut for BorrowMut<'_, T> {
+    fn deref_mut(&mut self) -> &mut T {
+        self.owner.value_mut()
+    }
+}
+
+impl<T> Drop for BorrowMut<'_, T> {
+    fn drop(&mut self) {
+        self.owner.log.push(Event::BorrowEnded { owner: self.owner.name.clone(), by: self.by.clone(), mutable: true });
+    }
+}